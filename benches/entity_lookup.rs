@@ -0,0 +1,43 @@
+use bevy::ecs::{component::Component, world::World};
+#[allow(deprecated)]
+use bevy_mod_debug_console::{get_archetype_id, get_archetype_id_by_entity_id};
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+
+// the values are only there to give the components a size
+#[derive(Component)]
+struct A(#[allow(dead_code)] u32);
+#[derive(Component)]
+struct B(#[allow(dead_code)] u32);
+#[derive(Component)]
+struct C(#[allow(dead_code)] u32);
+
+// spreads the entities over a few archetypes like a real level would
+fn large_world(entity_count: u32) -> World {
+    let mut world = World::new();
+    let batch = entity_count / 4;
+    world.spawn_batch((0..batch).map(|i| (A(i),)));
+    world.spawn_batch((0..batch).map(|i| (A(i), B(i))));
+    world.spawn_batch((0..batch).map(|i| (A(i), C(i))));
+    world.spawn_batch((0..batch).map(|i| (A(i), B(i), C(i))));
+
+    world
+}
+
+#[allow(deprecated)]
+fn entity_lookup(c: &mut Criterion) {
+    let world = large_world(200_000);
+    // the last spawned entity is the worst case when scanning archetypes
+    let entity_id = world.entities().len() - 1;
+
+    let mut group = c.benchmark_group("entity_lookup");
+    group.bench_function("scan_archetypes", |b| {
+        b.iter(|| get_archetype_id_by_entity_id(world.archetypes(), black_box(entity_id)))
+    });
+    group.bench_function("entities_get", |b| {
+        b.iter(|| get_archetype_id(world.entities(), black_box(entity_id)))
+    });
+    group.finish();
+}
+
+criterion_group!(benches, entity_lookup);
+criterion_main!(benches);
@@ -1,58 +1,33 @@
-// pausing game loop does not work with egui_console
 // press the GRAVE key to open the console
+// `pause` stops the systems in `GameStage` while the console keeps running
 
 use bevy::prelude::*;
-use bevy::{
-    ecs::{archetype::Archetypes, component::Components, entity::Entities},
-    reflect::TypeRegistry,
-};
-use bevy_console::{
-    ConsoleCommandEntered, ConsoleConfiguration, ConsolePlugin, FromValue, PrintConsoleLine,
-};
-use bevy_mod_debug_console::{build_commands, match_commands, Pause};
+use bevy_console::{ConsoleConfiguration, ConsolePlugin};
+use bevy_mod_debug_console::DebugConsoleEguiPlugin;
 
+// the name is only there to tell the example entities apart
 #[derive(Component)]
-struct Thing(String);
+struct Thing(#[allow(dead_code)] String);
 
-fn debug_console(
-    mut console_events: EventReader<ConsoleCommandEntered>,
-    mut console_line: EventWriter<PrintConsoleLine>,
-    a: &Archetypes,
-    c: &Components,
-    e: &Entities,
-    mut pause: ResMut<Pause>,
-    reflect: Res<TypeRegistry>,
-) {
-    let app_name = "";
-    for event in console_events.iter() {
-        let console_app = build_commands(app_name);
-        let mut args = vec![app_name];
-        args.push(&event.command);
-
-        let split: Vec<String> = event
-            .args
-            .iter()
-            .filter_map(|x| String::from_value(x, 0).ok())
-            .collect();
-        args.append(&mut split.iter().map(|s| s.as_ref()).collect());
-
-        let matches_result = console_app.try_get_matches_from(args);
-
-        if let Err(e) = matches_result {
-            console_line.send(PrintConsoleLine::new(e.to_string()));
-            return;
-        }
-
-        let output = match_commands(&matches_result.unwrap(), a, c, e, &mut pause, &*reflect);
-
-        console_line.send(PrintConsoleLine::new(output));
-    }
-}
+#[derive(StageLabel)]
+struct GameStage;
 
 fn setup(mut commands: Commands) {
     // Adds some Entities to test out `entities list` command
-    commands.spawn().insert(Thing("Entity 1".to_string()));
-    commands.spawn().insert(Thing("Entity 2".to_string()));
+    commands
+        .spawn()
+        .insert(Thing("Entity 1".to_string()))
+        .insert(Transform::default());
+    commands
+        .spawn()
+        .insert(Thing("Entity 2".to_string()))
+        .insert(Transform::default());
+}
+
+fn spin(mut things: Query<&mut Transform, With<Thing>>, time: Res<Time>) {
+    for mut transform in things.iter_mut() {
+        transform.rotate_z(time.delta_seconds());
+    }
 }
 
 fn main() {
@@ -63,8 +38,11 @@ fn main() {
             ..Default::default()
         })
         .add_plugin(ConsolePlugin)
-        .insert_resource(Pause(false))
+        .add_stage_after(CoreStage::Update, GameStage, SystemStage::parallel())
+        .add_plugin(DebugConsoleEguiPlugin {
+            pause_stages: vec![GameStage.as_label()],
+        })
         .add_startup_system(setup)
-        .add_system(debug_console)
+        .add_system_to_stage(GameStage, spin)
         .run();
 }
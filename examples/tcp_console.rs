@@ -0,0 +1,11 @@
+// connect with `nc 127.0.0.1 7777` or any other line based tcp client
+
+use bevy::prelude::*;
+use bevy_mod_debug_console::TcpConsolePlugin;
+
+fn main() {
+    App::new()
+        .add_plugins(DefaultPlugins)
+        .add_plugin(TcpConsolePlugin::default())
+        .run();
+}
@@ -1,70 +1,278 @@
+use crate::command;
 use crate::ecs;
+use crate::events;
+use crate::output::CommandOutput;
+use crate::profile;
 use crate::reflect;
+use crate::schedule;
 use bevy::{
-    ecs::{archetype::Archetypes, component::Components, entity::Entities, schedule::ShouldRun},
+    app::{App as BevyApp, CoreStage},
+    ecs::{
+        schedule::{ShouldRun, StageLabel, StageLabelId, SystemStage},
+        world::{Mut, World},
+    },
+    log::info,
     prelude::{Input, KeyCode, Local, Res, ResMut},
     reflect::TypeRegistry,
+    time::Time,
+    utils::{Duration, Instant},
 };
-use clap::{App, ArgMatches};
+use clap::{arg, App, ArgMatches, ErrorKind};
+use serde::Deserialize;
+use serde_json::{json, Value};
 use std::process::exit;
 
-pub fn build_commands<'a>(app_name: &'a str) -> App {
+pub fn build_commands<'a>(app_name: &'a str) -> App<'a> {
     let app = App::new(app_name);
 
     let app = build_app_commands(app);
     let app = ecs::build_commands(app);
     let app = reflect::build_commands(app);
+    let app = schedule::build_commands(app);
+    let app = profile::build_commands(app);
+    let app = events::build_commands(app);
 
     app
 }
 
-pub fn match_commands(
+/// Builds the console commands including the ones added with
+/// `add_console_command`.
+pub fn build_world_commands(app_name: &'static str, world: &World) -> App<'static> {
+    command::build_commands(build_commands(app_name), world)
+}
+
+pub fn match_commands(matches: &ArgMatches, world: &mut World, pause: &mut Pause) -> String {
+    match_commands_output(matches, world, pause).to_string()
+}
+
+/// Runs the matched command and returns its typed result, which can be
+/// printed as console text or serialized to json.
+pub fn match_commands_output(
     matches: &ArgMatches,
-    a: &Archetypes,
-    c: &Components,
-    e: &Entities,
+    world: &mut World,
     pause: &mut Pause,
-    reflect: &TypeRegistry,
-) -> String {
-    let mut output = String::new();
+) -> CommandOutput {
+    match_app_commands(matches, world, pause)
+        .or_else(|| ecs::match_commands(matches, world))
+        .or_else(|| reflect::match_commands(matches, world.resource::<TypeRegistry>()))
+        .or_else(|| schedule::match_commands(matches, world))
+        .or_else(|| profile::match_commands(matches, world))
+        .or_else(|| events::match_commands(matches, world))
+        .or_else(|| command::match_commands(matches, world).map(CommandOutput::Text))
+        .unwrap_or_else(|| CommandOutput::Text(String::new()))
+}
+
+/// Parses and runs one line typed into a console, returning the text to show
+/// to the user.
+pub fn run_command_line(world: &mut World, line: &str) -> String {
+    run_command_args(world, split_args(line))
+}
+
+/// Runs a command that is already split into arguments, i.e. by another
+/// console, returning the text to show to the user.
+pub fn run_command_args(world: &mut World, args: Vec<String>) -> String {
+    let app_name = "";
+    let mut args = args;
+    args.insert(0, String::from(app_name));
+
+    match build_world_commands(app_name, world).try_get_matches_from(args) {
+        Ok(matches) => world.resource_scope(|world, mut pause: Mut<Pause>| {
+            match_commands(&matches, world, &mut pause)
+        }),
+        Err(e) => e.to_string(),
+    }
+}
+
+#[derive(Deserialize)]
+struct JsonRpcRequest {
+    #[serde(default)]
+    id: Value,
+    method: String,
+    #[serde(default)]
+    params: Vec<String>,
+}
+
+/// Runs one line delimited JSON-RPC 2.0 request and returns the json response.
+/// The method is the top level command and the params are the rest of its
+/// arguments, i.e. `{"jsonrpc": "2.0", "id": 1, "method": "entities", "params": ["info", "--id", "3"]}`.
+pub fn run_json_rpc(world: &mut World, request: &str) -> String {
+    let request: JsonRpcRequest = match serde_json::from_str(request) {
+        Ok(request) => request,
+        Err(err) => return json_rpc_error(Value::Null, -32700, &err.to_string()),
+    };
+
+    // clap reports an unknown subcommand as an unexpected argument
+    let app_name = "";
+    let commands = build_world_commands(app_name, world);
+    if request.method != "help" && commands.find_subcommand(&request.method).is_none() {
+        let message = format!("Method not found: {}", request.method);
+        return json_rpc_error(request.id, -32601, &message);
+    }
+
+    let mut args = vec![String::from(app_name), request.method];
+    args.extend(request.params);
+
+    match commands.try_get_matches_from(args) {
+        Ok(matches) => {
+            let output = world.resource_scope(|world, mut pause: Mut<Pause>| {
+                match_commands_output(&matches, world, &mut pause)
+            });
+            match output {
+                CommandOutput::Error(message) => json_rpc_error(request.id, -32000, &message),
+                output => json_rpc_result(request.id, output),
+            }
+        }
+        // clap returns the help as an error, but asking for it is a success
+        Err(e) if matches!(e.kind(), ErrorKind::DisplayHelp | ErrorKind::DisplayVersion) => {
+            json_rpc_result(request.id, CommandOutput::Text(e.to_string()))
+        }
+        Err(e)
+            if matches!(
+                e.kind(),
+                ErrorKind::UnrecognizedSubcommand | ErrorKind::InvalidSubcommand
+            ) =>
+        {
+            json_rpc_error(request.id, -32601, &e.to_string())
+        }
+        Err(e) => json_rpc_error(request.id, -32602, &e.to_string()),
+    }
+}
+
+fn json_rpc_result(id: Value, output: CommandOutput) -> String {
+    json!({"jsonrpc": "2.0", "id": id, "result": output}).to_string()
+}
+
+fn json_rpc_error(id: Value, code: i32, message: &str) -> String {
+    json!({
+        "jsonrpc": "2.0",
+        "id": id,
+        "error": {"code": code, "message": message.trim_end()},
+    })
+    .to_string()
+}
+
+/// Splits a console line into arguments on whitespace. Double quoted text is
+/// kept together with its quotes, i.e. `--id name:"Player One"` is two arguments.
+pub fn split_args(line: &str) -> Vec<String> {
+    let mut args = Vec::new();
+    let mut current = String::new();
+    let mut in_quotes = false;
 
-    output.push_str(&match_app_commands(matches, pause));
-    output.push_str(&ecs::match_commands(matches, a, c, e));
-    output.push_str(&reflect::match_commands(matches, reflect));
+    for c in line.chars() {
+        if c == '"' {
+            in_quotes = !in_quotes;
+        }
+        if c.is_whitespace() && !in_quotes {
+            if !current.is_empty() {
+                args.push(std::mem::take(&mut current));
+            }
+        } else {
+            current.push(c);
+        }
+    }
+    if !current.is_empty() {
+        args.push(current);
+    }
 
-    output
+    args
 }
 
 fn build_app_commands(app: App) -> App {
     let app = app
         .subcommand(App::new("resume").about("resume running game"))
         .subcommand(App::new("pause").about("pause game tick"))
+        .subcommand(
+            App::new("step")
+                .about("run N frames and pause again")
+                .arg(arg!([N] "number of frames to run").default_value("1")),
+        )
+        .subcommand(
+            App::new("timescale")
+                .about("get or set the speed of game time, i.e. 0.5 for slow motion")
+                .arg(arg!([Scale] "new time scale")),
+        )
         .subcommand(App::new("quit").about("quit game"));
 
     app
 }
 
-fn match_app_commands(matches: &ArgMatches, mut pause: &mut Pause) -> String {
+fn match_app_commands(
+    matches: &ArgMatches,
+    world: &mut World,
+    pause: &mut Pause,
+) -> Option<CommandOutput> {
     let mut output = String::new();
     match matches.subcommand() {
         Some(("resume", _)) => {
+            cancel_step(world);
             pause.0 = false;
             output.push_str("...resuming game.");
         }
         Some(("pause", _)) => {
+            if let Err(output) = check_pause_stages(world) {
+                return Some(CommandOutput::Error(output));
+            }
+            cancel_step(world);
             pause.0 = true;
             output.push_str("pausing game...");
         }
+        Some(("step", matches)) => {
+            let frames: u32 = match matches.value_of_t("N") {
+                Ok(frames) if frames > 0 => frames,
+                _ => return Some(CommandOutput::Error(String::from("N must be at least 1\n"))),
+            };
+            if let Err(output) = check_pause_stages(world) {
+                return Some(CommandOutput::Error(output));
+            }
+            let mut step = match world.get_resource_mut::<FrameStep>() {
+                Some(step) => step,
+                None => {
+                    return Some(CommandOutput::Error(String::from(
+                        "No FrameStep resource found, frame stepping needs one of the console plugins\n",
+                    )))
+                }
+            };
+            step.remaining = frames;
+            let target = step.frame + frames as u64;
+            pause.0 = false;
+            output.push_str(&format!(
+                "stepping {} frames, pausing at frame {}\n",
+                frames, target
+            ));
+        }
+        Some(("timescale", matches)) => {
+            let mut time_scale = match world.get_resource_mut::<TimeScale>() {
+                Some(time_scale) => time_scale,
+                None => {
+                    return Some(CommandOutput::Error(String::from(
+                        "No TimeScale resource found, time scaling needs one of the console plugins\n",
+                    )))
+                }
+            };
+            if matches.is_present("Scale") {
+                match matches.value_of_t::<f32>("Scale") {
+                    Ok(scale) if scale >= 0.0 && scale.is_finite() => time_scale.scale = scale,
+                    _ => {
+                        return Some(CommandOutput::Error(String::from(
+                            "Scale must be a number that is 0 or larger\n",
+                        )))
+                    }
+                }
+            }
+            output.push_str(&format!("timescale: {}\n", time_scale.scale));
+        }
         Some(("quit", _)) => exit(0),
-        _ => {}
+        _ => return None,
     }
 
-    output
+    Some(output.into())
 }
 
 #[derive(Default)]
 pub struct Pause(pub bool);
 pub struct EnteringConsole(pub bool);
+
+/// Run criteria for the paused stages, skips them while the game is paused.
 pub fn pause(
     pause: Res<Pause>,
     mut last_pause: Local<Pause>,
@@ -73,14 +281,212 @@ pub fn pause(
     entering_console.0 = (pause.0 != last_pause.0) && pause.0;
     last_pause.0 = pause.0;
     if pause.0 {
-        ShouldRun::YesAndCheckAgain
+        ShouldRun::No
     } else {
         ShouldRun::Yes
     }
 }
 
+/// The stages that are skipped while the game is paused, `CoreStage::Update`
+/// by default. Insert it before adding the console plugin to pause other
+/// stages. The run criteria of these stages are replaced.
+pub struct PauseStages(pub Vec<StageLabelId>);
+
+impl Default for PauseStages {
+    fn default() -> Self {
+        PauseStages(vec![CoreStage::Update.as_label()])
+    }
+}
+
+/// `pause` would pause nothing without any stages to skip.
+fn check_pause_stages(world: &World) -> Result<(), String> {
+    match world.get_resource::<PauseStages>() {
+        Some(stages) if stages.0.is_empty() => Err(String::from(
+            "No stages to pause, add them to the console plugin's pause stages\n",
+        )),
+        _ => Ok(()),
+    }
+}
+
+pub fn add_pause_stages(app: &mut BevyApp, stages: &[StageLabelId]) {
+    for label in stages {
+        app.stage(*label, |stage: &mut SystemStage| stage.set_run_criteria(pause));
+    }
+}
+
+/// Stops a running `step`, so it doesn't pause the game again after a
+/// `resume` or `pause`.
+fn cancel_step(world: &mut World) {
+    if let Some(mut step) = world.get_resource_mut::<FrameStep>() {
+        step.remaining = 0;
+    }
+}
+
+/// Counts frames and pauses the game again once a `step` is done.
+#[derive(Default)]
+pub struct FrameStep {
+    pub frame: u64,
+    pub remaining: u32,
+}
+
+/// Drives `Time` from a clock that stops while the game is paused and runs at
+/// `scale` times real speed, so resuming doesn't produce one huge delta.
+///
+/// `scale_time` keeps its own copy of `Time` and overwrites bevy's `Time`
+/// resource with it every frame, right after bevy's time system. Any other
+/// changes made to `Time`, i.e. by another plugin, are lost on the next frame.
+pub struct TimeScale {
+    pub scale: f32,
+    time: Time,
+    start: Instant,
+    elapsed: Duration,
+    last_update: Option<Instant>,
+}
+
+impl Default for TimeScale {
+    fn default() -> Self {
+        TimeScale {
+            scale: 1.0,
+            time: Time::default(),
+            start: Instant::now(),
+            elapsed: Duration::ZERO,
+            last_update: None,
+        }
+    }
+}
+
+/// Replaces the real `Time` with the scaled clock, runs after bevy's time system.
+pub fn scale_time(mut time_scale: ResMut<TimeScale>, pause: Res<Pause>, mut time: ResMut<Time>) {
+    let now = Instant::now();
+    let real_delta = time_scale
+        .last_update
+        .map_or(Duration::ZERO, |last_update| now - last_update);
+    time_scale.last_update = Some(now);
+
+    if !pause.0 {
+        let scale = time_scale.scale;
+        time_scale.elapsed += real_delta.mul_f32(scale);
+    }
+
+    let instant = time_scale.start + time_scale.elapsed;
+    time_scale.time.update_with_instant(instant);
+    *time = time_scale.time.clone();
+}
+
+/// Runs at the end of the frame so a step runs the paused stages exactly once.
+pub fn count_frames(mut step: ResMut<FrameStep>, mut pause: ResMut<Pause>) {
+    step.frame += 1;
+    if step.remaining > 0 {
+        step.remaining -= 1;
+        if step.remaining == 0 {
+            pause.0 = true;
+            info!("step finished, paused at frame {}", step.frame);
+        }
+    }
+}
+
 pub fn input_pause(keyboard_input: Res<Input<KeyCode>>, mut pause: ResMut<Pause>) {
     if keyboard_input.pressed(KeyCode::F10) {
         pause.0 = true;
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn error_code(response: &str) -> Option<i64> {
+        let response: Value = serde_json::from_str(response).unwrap();
+        response["error"]["code"].as_i64()
+    }
+
+    fn test_world() -> World {
+        let mut world = World::new();
+        world.init_resource::<Pause>();
+        world.init_resource::<TypeRegistry>();
+        world
+    }
+
+    #[test]
+    fn split_args_splits_on_whitespace() {
+        assert_eq!(
+            split_args("  entities  info\t--id 3 "),
+            vec!["entities", "info", "--id", "3"]
+        );
+        assert!(split_args("").is_empty());
+    }
+
+    #[test]
+    fn split_args_keeps_quoted_text_together() {
+        assert_eq!(
+            split_args(r#"entities info --id name:"Player One""#),
+            vec!["entities", "info", "--id", r#"name:"Player One""#]
+        );
+    }
+
+    #[test]
+    fn json_rpc_returns_the_result() {
+        let mut world = test_world();
+        let response = run_json_rpc(
+            &mut world,
+            r#"{"jsonrpc": "2.0", "id": 7, "method": "counts"}"#,
+        );
+        let response: Value = serde_json::from_str(&response).unwrap();
+        assert_eq!(response["id"], 7);
+        assert_eq!(response["result"]["type"], "counts");
+    }
+
+    #[test]
+    fn json_rpc_returns_help_as_text() {
+        let mut world = test_world();
+        for request in [
+            r#"{"id": 1, "method": "help"}"#,
+            r#"{"id": 1, "method": "entities", "params": ["--help"]}"#,
+        ] {
+            let response: Value = serde_json::from_str(&run_json_rpc(&mut world, request)).unwrap();
+            assert_eq!(response["result"]["type"], "text", "{}", response);
+            assert!(response["error"].is_null(), "{}", response);
+        }
+    }
+
+    #[test]
+    fn json_rpc_error_codes() {
+        let mut world = test_world();
+        assert_eq!(
+            error_code(&run_json_rpc(&mut world, "{not json")),
+            Some(-32700)
+        );
+        assert_eq!(
+            error_code(&run_json_rpc(&mut world, r#"{"id": 1, "method": "nope"}"#)),
+            Some(-32601)
+        );
+        assert_eq!(
+            error_code(&run_json_rpc(
+                &mut world,
+                r#"{"id": 1, "method": "entities", "params": ["info", "--bogus"]}"#
+            )),
+            Some(-32602)
+        );
+        assert_eq!(
+            error_code(&run_json_rpc(
+                &mut world,
+                r#"{"id": 1, "method": "entities", "params": ["info", "--id", "999"]}"#
+            )),
+            Some(-32000)
+        );
+    }
+
+    #[test]
+    fn resume_and_pause_cancel_a_step() {
+        let mut world = test_world();
+        world.init_resource::<FrameStep>();
+        world.insert_resource(PauseStages::default());
+
+        for command in ["resume", "pause"] {
+            run_command_line(&mut world, "step 3");
+            assert_eq!(world.resource::<FrameStep>().remaining, 3);
+            run_command_line(&mut world, command);
+            assert_eq!(world.resource::<FrameStep>().remaining, 0, "{}", command);
+        }
+    }
+}
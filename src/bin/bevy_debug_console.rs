@@ -0,0 +1,166 @@
+// client for `TcpConsolePlugin`, run with
+// `cargo run --features client --bin bevy-debug-console -- 127.0.0.1:7777`
+
+use bevy_mod_debug_console::{build_commands, split_args};
+use clap::{arg, App, ErrorKind};
+use rustyline::{
+    completion::Completer, error::ReadlineError, highlight::Highlighter, hint::Hinter,
+    validate::Validator, Context, Editor, Helper,
+};
+use std::{
+    io::{self, Read, Write},
+    net::TcpStream,
+    process::exit,
+};
+
+const PROMPT: &str = ">>> ";
+
+struct CommandHelper {
+    commands: App<'static>,
+}
+
+impl Completer for CommandHelper {
+    type Candidate = String;
+
+    fn complete(
+        &self,
+        line: &str,
+        pos: usize,
+        _ctx: &Context<'_>,
+    ) -> rustyline::Result<(usize, Vec<String>)> {
+        let line = &line[..pos];
+        let start = line.rfind(|c: char| c.is_whitespace()).map_or(0, |i| i + 1);
+        let partial = &line[start..];
+
+        // walk down the subcommands that have already been typed
+        let mut command = &self.commands;
+        for word in line[..start].split_whitespace() {
+            if let Some(subcommand) = command.find_subcommand(word) {
+                command = subcommand;
+            }
+        }
+
+        let mut candidates: Vec<String> = command
+            .get_subcommands()
+            .map(|subcommand| String::from(subcommand.get_name()))
+            .chain(
+                command
+                    .get_arguments()
+                    .filter_map(|arg| arg.get_long())
+                    .map(|long| format!("--{}", long)),
+            )
+            .filter(|candidate| candidate.starts_with(partial))
+            .collect();
+        candidates.sort();
+
+        Ok((start, candidates))
+    }
+}
+
+impl Hinter for CommandHelper {
+    type Hint = String;
+}
+
+impl Highlighter for CommandHelper {}
+
+impl Validator for CommandHelper {}
+
+impl Helper for CommandHelper {}
+
+// the game ends every response with the prompt
+fn read_response(stream: &mut TcpStream) -> io::Result<String> {
+    let mut response = Vec::new();
+    let mut buffer = [0; 1024];
+    while !response.ends_with(PROMPT.as_bytes()) {
+        let read = stream.read(&mut buffer)?;
+        if read == 0 {
+            return Err(io::Error::new(
+                io::ErrorKind::UnexpectedEof,
+                "the game closed the connection",
+            ));
+        }
+        response.extend_from_slice(&buffer[..read]);
+    }
+    response.truncate(response.len() - PROMPT.len());
+
+    Ok(String::from_utf8_lossy(&response).into_owned())
+}
+
+fn main() {
+    let matches = App::new("bevy-debug-console")
+        .about("connect to a game running the TcpConsolePlugin")
+        .arg(arg!([Address] "address of the game").default_value("127.0.0.1:7777"))
+        .get_matches();
+    let address = matches.value_of("Address").unwrap();
+
+    let mut stream = match TcpStream::connect(address) {
+        Ok(stream) => stream,
+        Err(err) => {
+            eprintln!("could not connect to {}: {}", address, err);
+            exit(1);
+        }
+    };
+    match read_response(&mut stream) {
+        Ok(banner) => print!("{}", banner),
+        Err(err) => {
+            eprintln!("{}", err);
+            exit(1);
+        }
+    }
+
+    let mut commands = build_commands("");
+    let mut editor = Editor::<CommandHelper>::new().expect("could not start the prompt");
+    editor.set_helper(Some(CommandHelper {
+        commands: commands.clone(),
+    }));
+
+    loop {
+        let line = match editor.readline(PROMPT) {
+            Ok(line) => line,
+            Err(ReadlineError::Interrupted) | Err(ReadlineError::Eof) => break,
+            Err(err) => {
+                eprintln!("{}", err);
+                break;
+            }
+        };
+        if line.trim().is_empty() {
+            continue;
+        }
+        editor.add_history_entry(line.as_str());
+
+        // the game answers json-rpc requests without a prompt, so waiting for
+        // the response would never end
+        if line.starts_with('{') {
+            println!("json-rpc requests are not supported here, send them to the game directly");
+            continue;
+        }
+
+        // validate and print help without a round trip to the game, commands
+        // added by the game with `add_console_command` aren't known here so
+        // unknown subcommands are left for the game to check
+        let mut args = vec![String::new()];
+        args.append(&mut split_args(&line));
+        // clap reports an unknown top level command as an unexpected argument
+        let known = args[1] == "help" || commands.find_subcommand(&args[1]).is_some();
+        if let Err(err) = commands.try_get_matches_from_mut(args) {
+            let unknown = !known
+                || matches!(
+                    err.kind(),
+                    ErrorKind::UnrecognizedSubcommand | ErrorKind::InvalidSubcommand
+                );
+            if !unknown {
+                println!("{}", err);
+                continue;
+            }
+        }
+
+        let response = writeln!(stream, "{}", line).and_then(|_| read_response(&mut stream));
+        match response {
+            Ok(output) => print!("{}", output),
+            Err(err) => {
+                eprintln!("{}", err);
+                exit(1);
+            }
+        }
+    }
+}
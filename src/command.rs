@@ -0,0 +1,110 @@
+use crate::events;
+use crate::schedule;
+use bevy::{
+    app::App as BevyApp,
+    ecs::{event::Event, world::World},
+    reflect::Reflect,
+};
+use clap::{App, ArgMatches};
+
+/// A game specific console command. The clap `App` returned by `command` is
+/// added to the console next to the built in commands, so it shows up in
+/// `help`, and `run` is called with its matches whenever it is entered.
+///
+/// ```ignore
+/// struct Teleport;
+///
+/// impl ConsoleCommand for Teleport {
+///     fn command() -> App<'static> {
+///         App::new("teleport")
+///             .about("move the player")
+///             .arg(arg!(<X> "x position"))
+///             .arg(arg!(<Y> "y position"))
+///     }
+///
+///     fn run(matches: &ArgMatches, world: &mut World) -> String {
+///         ...
+///     }
+/// }
+///
+/// app.add_console_command::<Teleport>();
+/// ```
+pub trait ConsoleCommand: 'static {
+    fn command() -> App<'static>;
+    fn run(matches: &ArgMatches, world: &mut World) -> String;
+}
+
+struct RegisteredCommand {
+    name: String,
+    command: fn() -> App<'static>,
+    run: fn(&ArgMatches, &mut World) -> String,
+}
+
+/// The console commands added with `add_console_command`.
+#[derive(Default)]
+pub struct ConsoleCommands {
+    commands: Vec<RegisteredCommand>,
+}
+
+impl ConsoleCommands {
+    pub fn add<T: ConsoleCommand>(&mut self) {
+        let name = String::from(T::command().get_name());
+        self.commands.retain(|command| command.name != name);
+        self.commands.push(RegisteredCommand {
+            name,
+            command: T::command,
+            run: T::run,
+        });
+    }
+}
+
+pub trait AppConsoleExt {
+    fn add_console_command<T: ConsoleCommand>(&mut self) -> &mut Self;
+    /// Makes the schedule readable by the `schedule` commands. Call it after
+    /// all stages and systems are added, right before `run`.
+    fn add_schedule_commands(&mut self) -> &mut Self;
+    /// Lets `events read` print events of type `T`. `events list` shows the
+    /// buffered counts of registered events.
+    fn add_console_event<T: Event + Reflect>(&mut self) -> &mut Self;
+}
+
+impl AppConsoleExt for BevyApp {
+    fn add_console_command<T: ConsoleCommand>(&mut self) -> &mut Self {
+        self.init_resource::<ConsoleCommands>();
+        self.world.resource_mut::<ConsoleCommands>().add::<T>();
+        self
+    }
+
+    fn add_schedule_commands(&mut self) -> &mut Self {
+        schedule::wrap_schedule(self);
+        self
+    }
+
+    fn add_console_event<T: Event + Reflect>(&mut self) -> &mut Self {
+        events::add_event::<T>(self);
+        self
+    }
+}
+
+pub fn build_commands(app: App<'static>, world: &World) -> App<'static> {
+    let mut app = app;
+    if let Some(console_commands) = world.get_resource::<ConsoleCommands>() {
+        for command in &console_commands.commands {
+            app = app.subcommand((command.command)());
+        }
+    }
+
+    app
+}
+
+pub fn match_commands(matches: &ArgMatches, world: &mut World) -> Option<String> {
+    let (name, matches) = matches.subcommand()?;
+    let run = world
+        .get_resource::<ConsoleCommands>()?
+        .commands
+        .iter()
+        .find(|command| command.name == name)?
+        .run;
+
+    Some(run(matches, world))
+}
@@ -1,16 +1,26 @@
+use crate::output::{
+    ArchetypeInfo, ArchetypeSummary, CommandOutput, ComponentInfo, ComponentSummary, Counts,
+    EntityInfo, EntitySummary,
+};
+use crate::reflect::{deserialize_ron, find_registration, print_reflect, set_reflect_path};
+use crate::schedule::{get_schedule_info, ScheduleInfo};
 use bevy::{
+    core::Name,
     ecs::{
         archetype::{ArchetypeId, Archetypes},
         component::{ComponentId, Components, StorageType},
-        entity::{Entities, Entity},
+        entity::{Entities, Entity, EntityLocation},
+        reflect::{ReflectComponent, ReflectResource},
+        world::World,
     },
+    hierarchy::{despawn_with_children_recursive, BuildWorldChildren, Children, Parent},
+    reflect::{std_traits::ReflectDefault, Reflect, TypeRegistry},
     utils::get_short_name,
 };
 use clap::{App, AppSettings, ArgGroup, ArgMatches, arg};
+use std::{any::TypeId, collections::HashSet, str::FromStr};
 
-pub fn list_resources(archetypes: &Archetypes, components: &Components) -> String {
-    let mut output = String::new();
-
+pub fn list_resources(archetypes: &Archetypes, components: &Components) -> CommandOutput {
     let mut r: Vec<String> = archetypes
         .resource()
         .components()
@@ -25,11 +35,86 @@ pub fn list_resources(archetypes: &Archetypes, components: &Components) -> Strin
     // sort list alphebetically
     r.sort();
 
-    output.push_str("[resource name]\n");
-    r.iter()
-        .for_each(|name| output.push_str(&format!("{}\n", name)));
+    CommandOutput::ResourceList(r)
+}
 
-    output
+fn find_resource_id_by_name(
+    a: &Archetypes,
+    c: &Components,
+    resource_name: &str,
+) -> Result<ComponentId, String> {
+    let resources: Vec<(ComponentId, String)> = a
+        .resource()
+        .components()
+        .map(|id| (id, String::from(c.get_info(id).unwrap().name())))
+        .filter(|(_, name)| name == resource_name || get_short_name(name) == resource_name)
+        .collect();
+
+    match resources.len() {
+        0 => Err(format!("No resource found with name {}\n", resource_name)),
+        1 => Ok(resources[0].0),
+        _ => {
+            let mut output = String::new();
+            output.push_str(&format!(
+                "More than one resource found with name {}\n",
+                resource_name
+            ));
+            output.push_str("Consider using the full resource name instead\n\n");
+            output.push_str("[resource name]\n");
+            resources
+                .iter()
+                .for_each(|(_, name)| output.push_str(&format!("{}\n", name)));
+            Err(output)
+        }
+    }
+}
+
+fn get_reflect_resource(world: &World, resource_name: &str) -> Result<ReflectResource, String> {
+    let id = find_resource_id_by_name(world.archetypes(), world.components(), resource_name)?;
+    let type_id = world
+        .components()
+        .get_info(id)
+        .and_then(|info| info.type_id());
+
+    let type_registry = world.resource::<TypeRegistry>().read();
+    type_id
+        .and_then(|type_id| type_registry.get(type_id))
+        .and_then(|registration| registration.data::<ReflectResource>())
+        .cloned()
+        .ok_or_else(|| format!("{} is not reflected\n", resource_name))
+}
+
+fn inspect_resource(world: &World, resource_name: &str) -> Result<String, String> {
+    let reflect_resource = get_reflect_resource(world, resource_name)?;
+
+    let type_registry = world.resource::<TypeRegistry>().read();
+    match reflect_resource.reflect(world) {
+        Some(value) => Ok(format!(
+            "{}: {}",
+            resource_name,
+            print_reflect(&type_registry, value)
+        )),
+        None => Err(format!("No resource found with name {}\n", resource_name)),
+    }
+}
+
+fn set_resource_field(
+    world: &mut World,
+    resource_name: &str,
+    path: &str,
+    value: &str,
+) -> Result<String, String> {
+    let reflect_resource = get_reflect_resource(world, resource_name)?;
+
+    let mut resource = match reflect_resource.reflect_mut(world) {
+        Some(resource) => resource,
+        None => return Err(format!("No resource found with name {}\n", resource_name)),
+    };
+
+    match set_reflect_path(&mut *resource, path, value) {
+        Ok(()) => Ok(format!("{}.{} = {}\n", resource_name, path, value)),
+        Err(output) => Err(output),
+    }
 }
 
 fn get_components_by_name(
@@ -38,7 +123,7 @@ fn get_components_by_name(
     filter: Option<&str>,
 ) -> Vec<(usize, String)> {
     let mut names = Vec::new();
-    for id in 1..components.len() {
+    for id in 0..components.len() {
         if let Some(info) = components.get_info(ComponentId::new(id)) {
             if short {
                 names.push((id, get_short_name(info.name())));
@@ -59,65 +144,169 @@ fn get_components_by_name(
     }
 }
 
-fn list_components(c: &Components, short: bool, filter: Option<&str>) -> String {
-    let mut names = get_components_by_name(c, short, filter);
-    names.sort();
+fn find_component_id_by_name(c: &Components, component_name: &str) -> Result<usize, String> {
+    // only exact matches on the full or short name are accepted here, as the
+    // caller needs a single component
+    let components: Vec<(usize, String)> = get_components_by_name(c, false, Some(component_name))
+        .into_iter()
+        .filter(|(_, name)| name == component_name || get_short_name(name) == component_name)
+        .collect();
+
+    match components.len() {
+        0 => Err(format!("No component found with name {}\n", component_name)),
+        1 => Ok(components[0].0),
+        _ => {
+            let mut output = String::new();
+            output.push_str(&format!(
+                "More than one component found with name {}\n",
+                component_name
+            ));
+            output.push_str("Consider using the full component name instead\n\n");
+            output.push_str("[component id] [component name]\n");
+            components
+                .iter()
+                .for_each(|(id, name)| output.push_str(&format!("{} {}\n", id, name)));
+            Err(output)
+        }
+    }
+}
+
+/// Returns the systems that write and the systems that only read the component,
+/// as `stage system`.
+fn get_component_access(
+    info: &ScheduleInfo,
+    component_id: ComponentId,
+) -> (Vec<String>, Vec<String>) {
+    let mut writes = Vec::new();
+    let mut reads = Vec::new();
+    for stage in &info.stages {
+        for system in stage.systems.iter().flatten() {
+            if let Some(access) = &system.access {
+                if access.has_write(component_id) {
+                    writes.push(format!("{} {}", stage.label, system.name));
+                } else if access.has_read(component_id) {
+                    reads.push(format!("{} {}", stage.label, system.name));
+                }
+            }
+        }
+    }
+
+    (writes, reads)
+}
+
+fn print_component_access(world: &World, c: &Components, component_name: &str) -> CommandOutput {
+    let info = match get_schedule_info(world) {
+        Ok(info) => info,
+        Err(output) => return CommandOutput::Error(output),
+    };
+    let id = match find_component_id_by_name(c, component_name) {
+        Ok(id) => id,
+        Err(output) => return CommandOutput::Error(output),
+    };
+
+    let (writes, reads) = get_component_access(info, ComponentId::new(id));
+    if writes.is_empty() && reads.is_empty() {
+        return format!("no parallel system reads or writes {}\n", component_name).into();
+    }
 
     let mut output = String::new();
+    output.push_str(&format!("writes ({}):\n", writes.len()));
+    writes
+        .iter()
+        .for_each(|system| output.push_str(&format!("  {}\n", system)));
+    output.push_str(&format!("reads ({}):\n", reads.len()));
+    reads
+        .iter()
+        .for_each(|system| output.push_str(&format!("  {}\n", system)));
+
+    output.into()
+}
+
+fn list_unused_components(world: &World, c: &Components) -> CommandOutput {
+    let info = match get_schedule_info(world) {
+        Ok(info) => info,
+        Err(output) => return CommandOutput::Error(output),
+    };
+
+    let mut output = String::new();
+    output.push_str("not accessed by any parallel system, exclusive systems are not checked:\n");
     output.push_str("[component id] [component name]\n");
-    names
+    get_components_by_name(c, false, None)
         .iter()
+        .filter(|(id, _)| {
+            let (writes, reads) = get_component_access(info, ComponentId::new(*id));
+            writes.is_empty() && reads.is_empty()
+        })
         .for_each(|(id, name)| output.push_str(&format!("{} {}\n", id, name)));
 
-    output
+    output.into()
 }
 
-fn list_entities(e: &Entities) -> String {
-    let mut output = String::new();
-    output.push_str(&format!("[entity index] [archetype id]\n"));
+fn list_components(c: &Components, short: bool, filter: Option<&str>) -> CommandOutput {
+    let mut names = get_components_by_name(c, short, filter);
+    names.sort();
+
+    CommandOutput::ComponentList(
+        names
+            .into_iter()
+            .map(|(id, name)| ComponentSummary { id, name })
+            .collect(),
+    )
+}
+
+fn list_entities(e: &Entities) -> CommandOutput {
+    let mut entities = Vec::new();
     for id in 0..e.len() {
         if let Some(entity) = e.resolve_from_id(id) {
             if let Some(location) = e.get(entity) {
-                output.push_str(&format!("{} {}\n", id, location.archetype_id.index()));
+                entities.push(EntitySummary {
+                    entity: entity.into(),
+                    archetype_id: location.archetype_id.index(),
+                });
             }
         }
     }
 
-    output
+    CommandOutput::EntityList(entities)
 }
 
-fn list_archetypes(a: &Archetypes) -> String {
-    let mut output = String::new();
-    output.push_str(&format!("[id] [entity count]\n"));
-    a.iter().for_each(|archetype| {
-        output.push_str(&format!(
-            "{} {}\n",
-            archetype.id().index(),
-            archetype.entities().iter().count()
-        ))
-    });
+fn list_archetypes(a: &Archetypes) -> CommandOutput {
+    CommandOutput::ArchetypeList(
+        a.iter()
+            .map(|archetype| ArchetypeSummary {
+                id: archetype.id().index(),
+                entity_count: archetype.entities().len(),
+            })
+            .collect(),
+    )
+}
 
-    output
+fn print_ecs_counts(a: &Archetypes, c: &Components, e: &Entities) -> CommandOutput {
+    CommandOutput::Counts(Counts {
+        entities: e.len(),
+        components: c.len(),
+        archetypes: a.len(),
+    })
 }
 
-fn print_ecs_counts(a: &Archetypes, c: &Components, e: &Entities) -> String {
-    String::from(format!(
-        "entities: {}, components: {}, archetypes: {}\n",
-        e.len(),
-        c.len(),
-        a.len()
-    ))
+fn get_component_summaries(c: &Components, ids: &[ComponentId]) -> Vec<ComponentSummary> {
+    ids.iter()
+        .map(|id| ComponentSummary {
+            id: id.index(),
+            name: get_short_name(c.get_info(*id).unwrap().name()),
+        })
+        .collect()
 }
 
 fn find_archetypes_by_component_name(
     a: &Archetypes,
     c: &Components,
     component_name: &str,
-) -> String {
+) -> Result<String, String> {
     let components = get_components_by_name(c, false, Some(component_name));
 
     if components.len() == 0 {
-        return String::from(format!("No component found with name {}\n", component_name));
+        return Err(format!("No component found with name {}\n", component_name));
     }
 
     if components.len() > 1 {
@@ -133,15 +322,15 @@ fn find_archetypes_by_component_name(
         components
             .iter()
             .for_each(|(id, name)| output.push_str(&format!("{} {}\n", id, name)));
-        return output;
+        return Err(output);
     }
 
     if let Some(id_name) = components.iter().next() {
-        return find_archetypes_by_component_id(a, id_name.0);
+        return Ok(find_archetypes_by_component_id(a, id_name.0));
     };
 
     // should never be hit as clap
-    String::from("unsupported command")
+    Err(String::from("unsupported command"))
 }
 
 fn find_archetypes_by_component_id(a: &Archetypes, component_id: usize) -> String {
@@ -159,6 +348,17 @@ fn find_archetypes_by_component_id(a: &Archetypes, component_id: usize) -> Strin
     output
 }
 
+/// Finds the location of the entity with index `entity_id` without scanning
+/// the archetypes.
+pub fn get_entity_location(e: &Entities, entity_id: u32) -> Option<EntityLocation> {
+    resolve_entity(e, entity_id).and_then(|entity| e.get(entity))
+}
+
+pub fn get_archetype_id(e: &Entities, entity_id: u32) -> Option<usize> {
+    get_entity_location(e, entity_id).map(|location| location.archetype_id.index())
+}
+
+#[deprecated(note = "scans every archetype, use `get_archetype_id` instead")]
 pub fn get_archetype_id_by_entity_id(a: &Archetypes, entity_id: u32) -> Option<usize> {
     let mut archetypes = a
         .iter()
@@ -168,10 +368,10 @@ pub fn get_archetype_id_by_entity_id(a: &Archetypes, entity_id: u32) -> Option<u
     archetypes.next()
 }
 
-fn find_archetype_by_entity_id(a: &Archetypes, entity_id: u32) -> String {
+fn find_archetype_by_entity(e: &Entities, entity: Entity) -> String {
     let mut output = String::new();
 
-    let archetype_id = get_archetype_id_by_entity_id(a, entity_id);
+    let archetype_id = e.get(entity).map(|location| location.archetype_id.index());
 
     output.push_str(&format!("archetype id:\n"));
     if let Some(id) = archetype_id {
@@ -199,14 +399,71 @@ fn find_entities_by_component_id(a: &Archetypes, component_id: usize) -> String
     output.push_str(&format!("entity ids:\n"));
     entities
         .iter()
-        .for_each(|id| output.push_str(&format!("{}, ", id.id())));
+        .for_each(|entity| output.push_str(&format!("{:?}, ", entity)));
     output.push_str("\n");
 
     output
 }
 
-fn find_entities_by_component_name(a: &Archetypes, c: &Components, component_name: &str) -> String {
+fn query_entities(
+    a: &Archetypes,
+    c: &Components,
+    with: &[&str],
+    without: &[&str],
+) -> Result<String, String> {
+    let mut with_ids = Vec::new();
+    for name in with {
+        match find_component_id_by_name(c, name) {
+            Ok(id) => with_ids.push(ComponentId::new(id)),
+            Err(output) => return Err(output),
+        }
+    }
+    let mut without_ids = Vec::new();
+    for name in without {
+        match find_component_id_by_name(c, name) {
+            Ok(id) => without_ids.push(ComponentId::new(id)),
+            Err(output) => return Err(output),
+        }
+    }
+
+    // matching on the archetype's component set avoids checking every entity
+    let archetypes: Vec<_> = a
+        .iter()
+        .filter(|archetype| !archetype.entities().is_empty())
+        .filter(|archetype| with_ids.iter().all(|id| archetype.contains(*id)))
+        .filter(|archetype| !without_ids.iter().any(|id| archetype.contains(*id)))
+        .collect();
+
+    if archetypes.is_empty() {
+        return Ok(String::from("no entites found\n"));
+    }
+
+    let mut output = String::new();
+    archetypes.iter().for_each(|archetype| {
+        output.push_str(&format!(
+            "archetype id: {} ({} entities)\n",
+            archetype.id().index(),
+            archetype.entities().len()
+        ));
+        archetype
+            .entities()
+            .iter()
+            .for_each(|entity| output.push_str(&format!("{:?}, ", entity)));
+        output.push('\n');
+    });
+
+    Ok(output)
+}
+
+fn find_entities_by_component_name(
+    a: &Archetypes,
+    c: &Components,
+    component_name: &str,
+) -> Result<String, String> {
     let components = get_components_by_name(c, false, Some(component_name));
+    if components.is_empty() {
+        return Err(format!("No component found with name {}\n", component_name));
+    }
 
     let mut output = String::new();
     components.iter().for_each(|(id, name)| {
@@ -215,95 +472,506 @@ fn find_entities_by_component_name(a: &Archetypes, c: &Components, component_nam
         output.push_str("\n");
     });
 
-    output
+    Ok(output)
 }
 
-fn print_archetype(a: &Archetypes, c: &Components, archetype_id: ArchetypeId) -> String {
-    let mut output = String::new();
-    if let Some(archetype) = a.get(archetype_id) {
-        output.push_str(&format!("id: {:?}\n", archetype.id()));
-        output.push_str(&format!("table_id: {:?}\n", archetype.table_id()));
-        output.push_str(&format!(
-            "entities ({}): ",
-            archetype.entities().iter().count()
-        ));
-        archetype
-            .entities()
-            .iter()
-            .for_each(|entity| output.push_str(&format!("{}, ", entity.id())));
-        output.push_str(&format!("\n"));
-        // not sure what entity table rows is, so commenting out for now
-        // print!(
-        //     "entity table rows ({}): ",
-        //     archetype.entity_table_rows().iter().count()
-        // );
-        // archetype
-        //     .entity_table_rows()
-        //     .iter()
-        //     .for_each(|row| print!("{}, ", row));
-        // println!("");
-        output.push_str(&format!(
-            "table_components ({}): ",
-            archetype.table_components().iter().count()
-        ));
-        archetype
-            .table_components()
+fn resolve_entity(e: &Entities, entity_id: u32) -> Option<Entity> {
+    e.resolve_from_id(entity_id)
+        .filter(|entity| e.get(*entity).is_some())
+}
+
+/// An entity as typed into the console: `42`, `42v3` (index and generation)
+/// or `name:"Player One"` to look it up by its `Name` component.
+pub enum EntityArg {
+    Index(u32),
+    IndexGeneration(u32, u32),
+    Name(String),
+}
+
+impl FromStr for EntityArg {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if let Some(name) = s.strip_prefix("name:") {
+            let name = name.trim_matches('"');
+            return Ok(EntityArg::Name(String::from(name)));
+        }
+
+        let invalid = || format!("'{}' is not an entity, use 42, 42v3 or name:\"Name\"", s);
+        match s.split_once('v') {
+            Some((index, generation)) => Ok(EntityArg::IndexGeneration(
+                index.parse().map_err(|_| invalid())?,
+                generation.parse().map_err(|_| invalid())?,
+            )),
+            None => Ok(EntityArg::Index(s.parse().map_err(|_| invalid())?)),
+        }
+    }
+}
+
+impl EntityArg {
+    pub fn resolve(&self, world: &World) -> Result<Entity, String> {
+        let e = world.entities();
+        match self {
+            EntityArg::Index(index) => resolve_entity(e, *index)
+                .ok_or_else(|| format!("No entity found with id: {}\n", index)),
+            EntityArg::IndexGeneration(index, generation) => match resolve_entity(e, *index) {
+                Some(entity) if entity.generation() == *generation => Ok(entity),
+                // the index was recycled, so this handle points at a different entity now
+                Some(entity) => Err(format!(
+                    "entity {}v{} was despawned; {} is now {:?}\n",
+                    index, generation, index, entity
+                )),
+                None => Err(format!("entity {}v{} was despawned\n", index, generation)),
+            },
+            EntityArg::Name(name) => {
+                let entities: Vec<Entity> = (0..e.len())
+                    .filter_map(|id| resolve_entity(e, id))
+                    .filter(|entity| {
+                        world
+                            .get::<Name>(*entity)
+                            .map_or(false, |entity_name| entity_name.as_str() == name)
+                    })
+                    .collect();
+
+                match entities.len() {
+                    0 => Err(format!("No entity found with name {}\n", name)),
+                    1 => Ok(entities[0]),
+                    _ => {
+                        let mut output = String::new();
+                        output.push_str(&format!(
+                            "More than one entity found with name {}\n",
+                            name
+                        ));
+                        output.push_str("Consider searching with the entity id instead\n\n");
+                        output.push_str("[entity] [archetype id]\n");
+                        entities.iter().for_each(|entity| {
+                            let location = e.get(*entity).unwrap();
+                            output.push_str(&format!(
+                                "{:?} {}\n",
+                                entity,
+                                location.archetype_id.index()
+                            ))
+                        });
+                        Err(output)
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn get_entity_arg(world: &World, matches: &ArgMatches, name: &str) -> Result<Entity, String> {
+    let arg: EntityArg = matches
+        .value_of_t(name)
+        .map_err(|err| format!("{}\n", err))?;
+    arg.resolve(world)
+}
+
+fn print_entity(a: &Archetypes, c: &Components, e: &Entities, entity: Entity) -> CommandOutput {
+    let location = e.get(entity).unwrap();
+    let archetype = a.get(location.archetype_id).unwrap();
+
+    CommandOutput::EntityInfo(EntityInfo {
+        entity: entity.into(),
+        archetype_id: location.archetype_id.index(),
+        table_id: archetype.table_id().index(),
+        table_row: archetype.entity_table_rows()[location.index],
+        table_components: get_component_summaries(c, archetype.table_components()),
+        sparse_set_components: get_component_summaries(c, archetype.sparse_set_components()),
+    })
+}
+
+fn entity_label(world: &World, entity: Entity) -> String {
+    match world.get::<Name>(entity) {
+        Some(name) => format!("{} ({:?})", name.as_str(), entity),
+        None => format!("{:?}", entity),
+    }
+}
+
+fn write_entity_tree(
+    world: &World,
+    entity: Entity,
+    depth: usize,
+    max_depth: Option<usize>,
+    visited: &mut HashSet<Entity>,
+    output: &mut String,
+    warnings: &mut Vec<String>,
+) {
+    let indent = "  ".repeat(depth);
+    output.push_str(&format!("{}{}\n", indent, entity_label(world, entity)));
+
+    // guards against cycles in a broken hierarchy
+    if !visited.insert(entity) {
+        warnings.push(format!("entity {:?} is part of a cycle", entity));
+        return;
+    }
+
+    let children = match world.get::<Children>(entity) {
+        Some(children) => children,
+        None => return,
+    };
+
+    if max_depth.map_or(false, |max_depth| depth >= max_depth) {
+        output.push_str(&format!("{}  ... ({} children)\n", indent, children.len()));
+        return;
+    }
+
+    for child in children.iter() {
+        if world.entities().get(*child).is_none() {
+            warnings.push(format!(
+                "entity {:?} lists despawned entity {:?} as a child",
+                entity, child
+            ));
+            continue;
+        }
+        match world.get::<Parent>(*child) {
+            Some(parent) if parent.get() == entity => {}
+            Some(parent) => warnings.push(format!(
+                "entity {:?} lists {:?} as a child, but its parent is {:?}",
+                entity,
+                child,
+                parent.get()
+            )),
+            None => warnings.push(format!(
+                "entity {:?} lists {:?} as a child, but it has no parent",
+                entity, child
+            )),
+        }
+        write_entity_tree(world, *child, depth + 1, max_depth, visited, output, warnings);
+    }
+}
+
+fn print_entity_tree(world: &World, root: Option<Entity>, max_depth: Option<usize>) -> String {
+    let e = world.entities();
+    let entities: Vec<Entity> = (0..e.len())
+        .filter_map(|id| resolve_entity(e, id))
+        .collect();
+
+    let roots = match root {
+        Some(root) => vec![root],
+        None => entities
             .iter()
-            .map(|id| (id.index(), c.get_info(*id).unwrap()))
-            .map(|(id, info)| (id, get_short_name(info.name())))
-            .for_each(|(id, name)| output.push_str(&format!("{} {}, ", id, name)));
-        output.push_str("\n");
+            .copied()
+            .filter(|entity| world.get::<Parent>(*entity).is_none())
+            .collect(),
+    };
 
-        output.push_str(&format!(
-            "sparse set components ({}): ",
-            archetype.sparse_set_components().iter().count()
-        ));
-        archetype
-            .sparse_set_components()
+    let mut output = String::new();
+    let mut warnings = Vec::new();
+    let mut visited = HashSet::new();
+    for root in roots {
+        write_entity_tree(world, root, 0, max_depth, &mut visited, &mut output, &mut warnings);
+    }
+
+    // the walk above only sees the hierarchy from the parent's side
+    for entity in entities {
+        if let Some(parent) = world.get::<Parent>(entity) {
+            let listed = world
+                .get::<Children>(parent.get())
+                .map_or(false, |children| children.contains(&entity));
+            if !listed {
+                warnings.push(format!(
+                    "entity {:?} lists {:?} as its parent, but it is not one of its children",
+                    entity,
+                    parent.get()
+                ));
+            }
+        }
+    }
+
+    if !warnings.is_empty() {
+        output.push_str("\nwarning: inconsistent hierarchy\n");
+        warnings
             .iter()
-            .map(|id| (id.index(), c.get_info(*id).unwrap()))
-            .map(|(id, info)| (id, get_short_name(info.name())))
-            .for_each(|(id, name)| output.push_str(&format!("{} {}, ", id, name)));
-        output.push_str(&format!("\n"));
-    } else {
-        output.push_str(&format!(
-            "No archetype found with id: {}\n",
-            archetype_id.index()
-        ));
+            .for_each(|warning| output.push_str(&format!("{}\n", warning)));
     }
 
     output
 }
 
-fn print_component(c: &Components, component_id: usize) -> String {
+fn inspect_entity(world: &World, entity: Entity) -> String {
     let mut output = String::new();
-    if let Some(info) = c.get_info(ComponentId::new(component_id)) {
-        output.push_str(&format!("Name: {}\n", info.name()));
-        output.push_str(&format!("Id: {}\n", info.id().index()));
-        output.push_str("StorageType: ");
-        match info.storage_type() {
-            StorageType::Table => output.push_str("Table\n"),
-            StorageType::SparseSet => output.push_str("SparseSet\n"),
+
+    let location = world.entities().get(entity).unwrap();
+    let archetype = world.archetypes().get(location.archetype_id).unwrap();
+
+    let type_registry = world.resource::<TypeRegistry>().read();
+
+    output.push_str(&format!("entity: {:?}\n", entity));
+    for component_id in archetype.components() {
+        let info = world.components().get_info(component_id).unwrap();
+        let name = get_short_name(info.name());
+
+        // components that aren't registered with `#[reflect(Component)]` can
+        // only be listed by name
+        let reflected = info
+            .type_id()
+            .and_then(|type_id| type_registry.get(type_id))
+            .and_then(|registration| registration.data::<ReflectComponent>())
+            .and_then(|reflect_component| reflect_component.reflect(world, entity));
+
+        match reflected {
+            Some(value) => {
+                output.push_str(&format!("{}: {}", name, print_reflect(&type_registry, value)))
+            }
+            None => output.push_str(&format!("{}: (not reflected)\n", name)),
         }
-        output.push_str(&format!("SendAndSync: {}\n", info.is_send_and_sync()));
-    } else {
-        output.push_str(&format!("No component found with id: {}", component_id));
     }
 
     output
 }
 
-fn print_component_by_name(c: &Components, component_name: &str) -> String {
-    let components = get_components_by_name(c, false, Some(component_name));
+fn set_entity_field(
+    world: &mut World,
+    entity: Entity,
+    path: &str,
+    value: &str,
+) -> Result<String, String> {
+    // `Transform.translation.x` -> (`Transform`, `translation.x`)
+    let (component_name, field_path) = match path.find(['.', '[']) {
+        Some(i) => (&path[..i], path[i..].strip_prefix('.').unwrap_or(&path[i..])),
+        None => (path, ""),
+    };
 
-    let mut output = String::new();
-    components
-        .iter()
-        .for_each(|(id, _)| output.push_str(&format!("{}\n", &print_component(c, *id))));
+    let component_id = find_component_id_by_name(world.components(), component_name)?;
+    let type_id = world
+        .components()
+        .get_info(ComponentId::new(component_id))
+        .and_then(|info| info.type_id());
+
+    let reflect_component = {
+        let type_registry = world.resource::<TypeRegistry>().read();
+        type_id
+            .and_then(|type_id| type_registry.get(type_id))
+            .and_then(|registration| registration.data::<ReflectComponent>())
+            .cloned()
+    };
+    let reflect_component = match reflect_component {
+        Some(reflect_component) => reflect_component,
+        None => return Err(format!("{} is not reflected\n", component_name)),
+    };
+
+    let mut component = match reflect_component.reflect_mut(world, entity) {
+        Some(component) => component,
+        None => {
+            return Err(format!(
+                "entity {:?} does not have component {}\n",
+                entity, component_name
+            ))
+        }
+    };
+
+    match set_reflect_path(&mut *component, field_path, value) {
+        Ok(()) => Ok(format!("{} = {}\n", path, value)),
+        Err(output) => Err(output),
+    }
+}
+
+fn despawn_entity(world: &mut World, entity: Entity, recursive: bool) -> Result<String, String> {
+    if recursive {
+        despawn_with_children_recursive(world, entity);
+        return Ok(format!("despawned entity {:?} and its children\n", entity));
+    }
+
+    if world.get::<Children>(entity).map_or(false, |children| !children.is_empty()) {
+        return Err(format!(
+            "entity {:?} has children, use --recursive to despawn them too\n",
+            entity
+        ));
+    }
+    // otherwise the parent keeps listing the despawned entity as its child
+    if let Some(parent) = world.get::<Parent>(entity).map(|parent| parent.get()) {
+        world.entity_mut(parent).remove_children(&[entity]);
+    }
+    world.despawn(entity);
+    Ok(format!("despawned entity {:?}\n", entity))
+}
+
+fn spawn_entity(world: &mut World, component_names: &[&str]) -> Result<String, String> {
+    // resolve every component before spawning so a typo doesn't leave behind
+    // a half built entity
+    let mut components: Vec<(ReflectComponent, Box<dyn Reflect>)> = Vec::new();
+    {
+        let type_registry = world.resource::<TypeRegistry>().read();
+        for name in component_names {
+            let registration = find_registration(&type_registry, name)?;
+            let reflect_component = match registration.data::<ReflectComponent>() {
+                Some(reflect_component) => reflect_component.clone(),
+                None => return Err(format!("{} does not reflect Component\n", name)),
+            };
+            let reflect_default = match registration.data::<ReflectDefault>() {
+                Some(reflect_default) => reflect_default,
+                None => return Err(format!("{} does not reflect Default\n", name)),
+            };
+            components.push((reflect_component, reflect_default.default()));
+        }
+    }
+
+    let entity = world.spawn().id();
+    for (reflect_component, value) in components {
+        reflect_component.insert(world, entity, &*value);
+    }
+
+    Ok(format!("spawned entity {:?}\n", entity))
+}
+
+fn clone_entity(world: &mut World, entity: Entity) -> String {
+    let location = world.entities().get(entity).unwrap();
+    let archetype = world.archetypes().get(location.archetype_id).unwrap();
+
+    let mut components: Vec<(ReflectComponent, Box<dyn Reflect>)> = Vec::new();
+    let mut skipped = Vec::new();
+    {
+        let type_registry = world.resource::<TypeRegistry>().read();
+        for component_id in archetype.components() {
+            let info = world.components().get_info(component_id).unwrap();
+            // copying the hierarchy components would leave the parent and
+            // children disagreeing about who belongs to whom
+            if info.type_id() == Some(TypeId::of::<Parent>())
+                || info.type_id() == Some(TypeId::of::<Children>())
+            {
+                skipped.push(get_short_name(info.name()));
+                continue;
+            }
+
+            let reflect_component = info
+                .type_id()
+                .and_then(|type_id| type_registry.get(type_id))
+                .and_then(|registration| registration.data::<ReflectComponent>());
+            match reflect_component {
+                Some(reflect_component) => {
+                    let value = reflect_component.reflect(world, entity).unwrap().clone_value();
+                    components.push((reflect_component.clone(), value));
+                }
+                None => skipped.push(get_short_name(info.name())),
+            }
+        }
+    }
+
+    let clone = world.spawn().id();
+    for (reflect_component, value) in components {
+        reflect_component.insert(world, clone, &*value);
+    }
+
+    let mut output = format!("cloned entity {:?} to {:?}\n", entity, clone);
+    if !skipped.is_empty() {
+        output.push_str(&format!("skipped components: {}\n", skipped.join(", ")));
+    }
 
     output
 }
 
+fn insert_component(
+    world: &mut World,
+    entity: Entity,
+    component_name: &str,
+    value: Option<&str>,
+) -> Result<String, String> {
+    let (reflect_component, component) = {
+        let type_registry = world.resource::<TypeRegistry>().read();
+        let registration = find_registration(&type_registry, component_name)?;
+        let reflect_component = match registration.data::<ReflectComponent>() {
+            Some(reflect_component) => reflect_component.clone(),
+            None => return Err(format!("{} does not reflect Component\n", component_name)),
+        };
+
+        let component = match value {
+            Some(value) => deserialize_ron(registration, value),
+            None => registration
+                .data::<ReflectDefault>()
+                .map(|reflect_default| reflect_default.default())
+                .ok_or_else(|| {
+                    format!(
+                        "{} does not reflect Default, a value is required\n",
+                        component_name
+                    )
+                }),
+        };
+        match component {
+            Ok(component) => (reflect_component, component),
+            Err(output) => return Err(output),
+        }
+    };
+
+    reflect_component.insert(world, entity, &*component);
+
+    Ok(format!("inserted {} on entity {:?}\n", component_name, entity))
+}
+
+fn remove_component(
+    world: &mut World,
+    entity: Entity,
+    component_name: &str,
+) -> Result<String, String> {
+    let reflect_component = {
+        let type_registry = world.resource::<TypeRegistry>().read();
+        let registration = find_registration(&type_registry, component_name)?;
+        match registration.data::<ReflectComponent>() {
+            Some(reflect_component) => reflect_component.clone(),
+            None => return Err(format!("{} does not reflect Component\n", component_name)),
+        }
+    };
+
+    if reflect_component.reflect(world, entity).is_none() {
+        return Err(format!(
+            "entity {:?} does not have component {}\n",
+            entity, component_name
+        ));
+    }
+    reflect_component.remove(world, entity);
+
+    Ok(format!("removed {} from entity {:?}\n", component_name, entity))
+}
+
+fn print_archetype(a: &Archetypes, c: &Components, archetype_id: ArchetypeId) -> CommandOutput {
+    if let Some(archetype) = a.get(archetype_id) {
+        CommandOutput::ArchetypeInfo(ArchetypeInfo {
+            id: archetype.id().index(),
+            table_id: archetype.table_id().index(),
+            entities: archetype.entities().iter().map(|entity| (*entity).into()).collect(),
+            // not sure what entity table rows is, so they are left out for now
+            table_components: get_component_summaries(c, archetype.table_components()),
+            sparse_set_components: get_component_summaries(c, archetype.sparse_set_components()),
+        })
+    } else {
+        CommandOutput::Error(format!(
+            "No archetype found with id: {}\n",
+            archetype_id.index()
+        ))
+    }
+}
+
+fn get_component_info(c: &Components, component_id: usize) -> Option<ComponentInfo> {
+    c.get_info(ComponentId::new(component_id))
+        .map(|info| ComponentInfo {
+            id: info.id().index(),
+            name: String::from(info.name()),
+            storage_type: match info.storage_type() {
+                StorageType::Table => String::from("Table"),
+                StorageType::SparseSet => String::from("SparseSet"),
+            },
+            send_and_sync: info.is_send_and_sync(),
+        })
+}
+
+fn print_component(c: &Components, component_id: usize) -> CommandOutput {
+    match get_component_info(c, component_id) {
+        Some(info) => CommandOutput::ComponentInfo(vec![info]),
+        None => CommandOutput::Error(format!("No component found with id: {}\n", component_id)),
+    }
+}
+
+fn print_component_by_name(c: &Components, component_name: &str) -> CommandOutput {
+    let components = get_components_by_name(c, false, Some(component_name));
+
+    CommandOutput::ComponentInfo(
+        components
+            .iter()
+            .filter_map(|(id, _)| get_component_info(c, *id))
+            .collect(),
+    )
+}
+
 pub fn build_commands<'a>(app: App<'a>) -> App<'a> {
     let app = app.subcommand(
             App::new("counts").about("print counts of archetypes, components, and entities"),
@@ -329,7 +997,7 @@ pub fn build_commands<'a>(app: App<'a>) -> App<'a> {
                     .args([
                         arg!(--componentid <ComponentId> "find types that have components with ComponentId"),
                         arg!(--componentname <ComponentName> "find types that have components with ComponentName"),
-                        arg!(--entityid <EntityId> "find types that have entities with EntityId")
+                        arg!(--entityid <Entity> "find types that have the entity, i.e. 42, 42v3 or name:\"Player\"")
                     ])
                     .group(ArgGroup::new("search params")
                         .args(&["componentid", "componentname", "entityid"])
@@ -360,6 +1028,17 @@ pub fn build_commands<'a>(app: App<'a>) -> App<'a> {
                         .required(true)
                     )
                 )
+                .subcommand(App::new("access")
+                    .about("list the systems that read or write a component or resource, exclusive systems are not included")
+                    .args([
+                        arg!([Name] "component or resource name"),
+                        arg!(--unused "list the components and resources no parallel system reads or writes")
+                    ])
+                    .group(ArgGroup::new("access params")
+                        .args(&["Name", "unused"])
+                        .required(true)
+                    )
+                )
         )
         .subcommand(
             App::new("entities")
@@ -369,6 +1048,86 @@ pub fn build_commands<'a>(app: App<'a>) -> App<'a> {
                     App::new("list")
                         .about("list all entities")
                 )
+                .subcommand(
+                    App::new("tree")
+                        .about("print the parent/children hierarchy")
+                        .args([
+                            arg!(--root [Entity] "only print the tree below this entity"),
+                            arg!(--depth [Depth] "maximum depth to print")
+                        ])
+                )
+                .subcommand(
+                    App::new("info")
+                        .about("get info of one entity")
+                        .arg(arg!(--id <Entity> "entity id to get"))
+                )
+                .subcommand(
+                    App::new("inspect")
+                        .about("print reflected component values of an entity")
+                        .arg(arg!(--id <Entity> "entity id to inspect"))
+                )
+                .subcommand(
+                    App::new("set")
+                        .about("set a reflected component field of an entity")
+                        .args([
+                            arg!(--id <Entity> "entity id to modify"),
+                            arg!(<Path> "path to the field, i.e. Transform.translation.x"),
+                            arg!(<Value> "new value of the field").allow_hyphen_values(true)
+                        ])
+                )
+                .subcommand(
+                    App::new("despawn")
+                        .about("despawn an entity")
+                        .args([
+                            arg!(--id <Entity> "entity id to despawn"),
+                            arg!(-r --recursive "also despawn its children, required if it has any")
+                        ])
+                )
+                .subcommand(
+                    App::new("spawn")
+                        .about("spawn an entity with default instances of the given components")
+                        .arg(arg!([Component] ... "names of reflected components with a default"))
+                )
+                .subcommand(
+                    App::new("clone")
+                        .about("spawn a copy of an entity with all of its reflected components")
+                        .arg(arg!(--id <Entity> "entity id to clone"))
+                )
+                .subcommand(
+                    App::new("insert")
+                        .about("insert a reflected component on an entity")
+                        .args([
+                            arg!(--id <Entity> "entity id to modify"),
+                            arg!(<Component> "name of the component"),
+                            arg!([Value] ... "ron value of the component, uses the default when omitted")
+                                .allow_hyphen_values(true)
+                        ])
+                )
+                .subcommand(
+                    App::new("remove")
+                        .about("remove a reflected component from an entity")
+                        .args([
+                            arg!(--id <Entity> "entity id to modify"),
+                            arg!(<Component> "name of the component")
+                        ])
+                )
+                .subcommand(
+                    App::new("query")
+                        .about("find entities that have all --with and none of the --without components")
+                        .args([
+                            arg!(--with <ComponentName> "component the entities must have")
+                                .required(false)
+                                .multiple_occurrences(true),
+                            arg!(--without <ComponentName> "component the entities must not have")
+                                .required(false)
+                                .multiple_occurrences(true)
+                        ])
+                        .group(ArgGroup::new("search params")
+                            .args(&["with", "without"])
+                            .multiple(true)
+                            .required(true)
+                        )
+                )
                 .subcommand(
                     App::new("find")
                         .about("find entity matching search params")
@@ -390,40 +1149,56 @@ pub fn build_commands<'a>(app: App<'a>) -> App<'a> {
                     App::new("list")
                         .about("list all resources")
                 )
+                .subcommand(
+                    App::new("inspect")
+                        .about("print the reflected value of a resource")
+                        .arg(arg!(<Name> "name of the resource"))
+                )
+                .subcommand(
+                    App::new("set")
+                        .about("set a reflected field of a resource")
+                        .args([
+                            arg!(<Name> "name of the resource"),
+                            arg!(<Path> "path to the field, i.e. translation.x"),
+                            arg!(<Value> "new value of the field").allow_hyphen_values(true)
+                        ])
+                )
         );
 
     app
 }
 
-pub fn match_commands(
-    matches: &ArgMatches,
-    a: &Archetypes,
-    c: &Components,
-    e: &Entities,
-) -> String {
-    match matches.subcommand() {
+pub fn match_commands(matches: &ArgMatches, world: &mut World) -> Option<CommandOutput> {
+    let a = world.archetypes();
+    let c = world.components();
+    let e = world.entities();
+
+    let output = match matches.subcommand() {
         Some(("archetypes", matches)) => match matches.subcommand() {
             Some(("list", _)) => list_archetypes(a),
             Some(("find", matches)) => {
                 if let Ok(component_id) = matches.value_of_t("componentid") {
-                    find_archetypes_by_component_id(a, component_id)
+                    find_archetypes_by_component_id(a, component_id).into()
                 } else if let Some(component_name) = matches.value_of("componentname") {
-                    find_archetypes_by_component_name(a, c, component_name)
-                } else if let Ok(entity_id) = matches.value_of_t("entityid") {
-                    find_archetype_by_entity_id(a, entity_id)
+                    find_archetypes_by_component_name(a, c, component_name).into()
+                } else if matches.is_present("entityid") {
+                    match get_entity_arg(world, matches, "entityid") {
+                        Ok(entity) => find_archetype_by_entity(e, entity).into(),
+                        Err(output) => CommandOutput::Error(output),
+                    }
                 } else {
                     // should never be hit as clap checks this
-                    String::from("this line should not be hittable")
+                    String::from("this line should not be hittable").into()
                 }
             }
             Some(("info", matches)) => {
                 if let Ok(id) = matches.value_of_t("id") {
                     print_archetype(a, c, ArchetypeId::new(id))
                 } else {
-                    String::from("this line should not be hittable")
+                    String::from("this line should not be hittable").into()
                 }
             }
-            _ => String::from("this line should not be hittable"),
+            _ => String::from("this line should not be hittable").into(),
         },
         Some(("components", matches)) => match matches.subcommand() {
             Some(("list", matches)) => {
@@ -435,29 +1210,246 @@ pub fn match_commands(
                 } else if let Some(name) = matches.value_of("name") {
                     print_component_by_name(c, name)
                 } else {
-                    String::from("this line should not be hittable")
+                    String::from("this line should not be hittable").into()
+                }
+            }
+            Some(("access", matches)) => {
+                if matches.is_present("unused") {
+                    list_unused_components(world, c)
+                } else if let Some(name) = matches.value_of("Name") {
+                    print_component_access(world, c, name)
+                } else {
+                    String::from("this line should not be hittable").into()
                 }
             }
-            _ => String::from("this line should not be hittable"),
+            _ => String::from("this line should not be hittable").into(),
         },
         Some(("entities", matches)) => match matches.subcommand() {
             Some(("list", _)) => list_entities(e),
+            Some(("tree", matches)) => {
+                let root = if matches.is_present("root") {
+                    match get_entity_arg(world, matches, "root") {
+                        Ok(root) => Some(root),
+                        Err(output) => return Some(CommandOutput::Error(output)),
+                    }
+                } else {
+                    None
+                };
+                let depth = if matches.is_present("depth") {
+                    match matches.value_of_t("depth") {
+                        Ok(depth) => Some(depth),
+                        Err(err) => return Some(CommandOutput::Error(err.to_string())),
+                    }
+                } else {
+                    None
+                };
+                print_entity_tree(world, root, depth).into()
+            }
+            Some(("info", matches)) => match get_entity_arg(world, matches, "id") {
+                Ok(entity) => print_entity(a, c, e, entity),
+                Err(output) => CommandOutput::Error(output),
+            },
+            Some(("inspect", matches)) => match get_entity_arg(world, matches, "id") {
+                Ok(entity) => inspect_entity(world, entity).into(),
+                Err(output) => CommandOutput::Error(output),
+            },
+            Some(("set", matches)) => {
+                let entity = match get_entity_arg(world, matches, "id") {
+                    Ok(entity) => entity,
+                    Err(output) => return Some(CommandOutput::Error(output)),
+                };
+                if let (Some(path), Some(value)) =
+                    (matches.value_of("Path"), matches.value_of("Value"))
+                {
+                    set_entity_field(world, entity, path, value).into()
+                } else {
+                    String::from("this line should not be hittable").into()
+                }
+            }
+            Some(("despawn", matches)) => match get_entity_arg(world, matches, "id") {
+                Ok(entity) => despawn_entity(world, entity, matches.is_present("recursive")).into(),
+                Err(output) => CommandOutput::Error(output),
+            },
+            Some(("spawn", matches)) => {
+                let components: Vec<&str> =
+                    matches.values_of("Component").into_iter().flatten().collect();
+                spawn_entity(world, &components).into()
+            }
+            Some(("clone", matches)) => match get_entity_arg(world, matches, "id") {
+                Ok(entity) => clone_entity(world, entity).into(),
+                Err(output) => CommandOutput::Error(output),
+            },
+            Some(("insert", matches)) => {
+                // the console splits the line on whitespace, so put the ron back together
+                let value = matches
+                    .values_of("Value")
+                    .map(|values| values.collect::<Vec<&str>>().join(" "));
+                let entity = match get_entity_arg(world, matches, "id") {
+                    Ok(entity) => entity,
+                    Err(output) => return Some(CommandOutput::Error(output)),
+                };
+                if let Some(component) = matches.value_of("Component") {
+                    insert_component(world, entity, component, value.as_deref()).into()
+                } else {
+                    String::from("this line should not be hittable").into()
+                }
+            }
+            Some(("remove", matches)) => {
+                let entity = match get_entity_arg(world, matches, "id") {
+                    Ok(entity) => entity,
+                    Err(output) => return Some(CommandOutput::Error(output)),
+                };
+                if let Some(component) = matches.value_of("Component") {
+                    remove_component(world, entity, component).into()
+                } else {
+                    String::from("this line should not be hittable").into()
+                }
+            }
+            Some(("query", matches)) => {
+                let with: Vec<&str> = matches.values_of("with").into_iter().flatten().collect();
+                let without: Vec<&str> =
+                    matches.values_of("without").into_iter().flatten().collect();
+                query_entities(a, c, &with, &without).into()
+            }
             Some(("find", matches)) => {
                 if let Ok(component_id) = matches.value_of_t("componentid") {
-                    find_entities_by_component_id(a, component_id)
+                    find_entities_by_component_id(a, component_id).into()
                 } else if let Some(component_name) = matches.value_of("componentname") {
-                    find_entities_by_component_name(a, c, component_name)
+                    find_entities_by_component_name(a, c, component_name).into()
                 } else {
-                    String::from("this line should not be hittable")
+                    String::from("this line should not be hittable").into()
                 }
             }
-            _ => String::from("this line should not be hittable"),
+            _ => String::from("this line should not be hittable").into(),
         },
         Some(("resources", matches)) => match matches.subcommand() {
             Some(("list", _)) => list_resources(a, c),
-            _ => String::from("this line should not be hittable"),
+            Some(("inspect", matches)) => {
+                if let Some(name) = matches.value_of("Name") {
+                    inspect_resource(world, name).into()
+                } else {
+                    String::from("this line should not be hittable").into()
+                }
+            }
+            Some(("set", matches)) => {
+                if let (Some(name), Some(path), Some(value)) = (
+                    matches.value_of("Name"),
+                    matches.value_of("Path"),
+                    matches.value_of("Value"),
+                ) {
+                    set_resource_field(world, name, path, value).into()
+                } else {
+                    String::from("this line should not be hittable").into()
+                }
+            }
+            _ => String::from("this line should not be hittable").into(),
         },
         Some(("counts", _)) => print_ecs_counts(a, c, e),
-        _ => String::from(""),
+        _ => return None,
+    };
+
+    Some(output)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy::ecs::component::Component;
+
+    #[derive(Component)]
+    struct Velocity;
+
+    mod other {
+        use bevy::ecs::component::Component;
+
+        #[derive(Component)]
+        pub struct Velocity;
+    }
+
+    #[derive(Component)]
+    struct Health;
+
+    fn entity_arg(s: &str) -> Result<EntityArg, String> {
+        s.parse()
+    }
+
+    #[test]
+    fn entity_arg_parses_ids_and_names() {
+        assert!(matches!(entity_arg("42"), Ok(EntityArg::Index(42))));
+        assert!(matches!(
+            entity_arg("42v3"),
+            Ok(EntityArg::IndexGeneration(42, 3))
+        ));
+        assert!(
+            matches!(entity_arg(r#"name:"Player One""#), Ok(EntityArg::Name(name)) if name == "Player One")
+        );
+        assert!(entity_arg("player").is_err());
+        assert!(entity_arg("42v").is_err());
+    }
+
+    #[test]
+    fn entity_arg_resolves_entities() {
+        let mut world = World::new();
+        let player = world.spawn().insert(Name::new("Player")).id();
+        world.spawn().insert(Name::new("Enemy"));
+        world.spawn().insert(Name::new("Enemy"));
+
+        assert_eq!(EntityArg::Index(player.id()).resolve(&world), Ok(player));
+        assert_eq!(
+            EntityArg::IndexGeneration(player.id(), player.generation()).resolve(&world),
+            Ok(player)
+        );
+        assert_eq!(
+            EntityArg::Name(String::from("Player")).resolve(&world),
+            Ok(player)
+        );
+        let enemy = EntityArg::Name(String::from("Enemy"));
+        assert!(enemy.resolve(&world).is_err());
+        let boss = EntityArg::Name(String::from("Boss"));
+        assert!(boss.resolve(&world).is_err());
+
+        world.despawn(player);
+        let recycled = world.spawn().id();
+        assert_eq!(recycled.id(), player.id());
+        assert!(EntityArg::IndexGeneration(player.id(), player.generation())
+            .resolve(&world)
+            .is_err());
+        assert!(EntityArg::Index(999).resolve(&world).is_err());
+    }
+
+    #[test]
+    fn find_component_id_by_name_needs_a_single_match() {
+        let mut world = World::new();
+        let velocity = world.init_component::<Velocity>();
+        world.init_component::<other::Velocity>();
+        let health = world.init_component::<Health>();
+        let c = world.components();
+
+        assert_eq!(find_component_id_by_name(c, "Health"), Ok(health.index()));
+        assert_eq!(
+            find_component_id_by_name(c, std::any::type_name::<Velocity>()),
+            Ok(velocity.index())
+        );
+        // the short name is ambiguous and a part of a name isn't enough
+        assert!(find_component_id_by_name(c, "Velocity").is_err());
+        assert!(find_component_id_by_name(c, "Heal").is_err());
+    }
+
+    #[test]
+    fn despawn_entity_keeps_the_hierarchy_valid() {
+        let mut world = World::new();
+        let child = world.spawn().id();
+        let other_child = world.spawn().id();
+        let parent = world.spawn().push_children(&[child, other_child]).id();
+
+        assert!(despawn_entity(&mut world, parent, false).is_err());
+        assert!(world.get_entity(parent).is_some());
+
+        assert!(despawn_entity(&mut world, child, false).is_ok());
+        let children = world.get::<Children>(parent).unwrap();
+        assert_eq!(&children[..], &[other_child]);
+
+        assert!(despawn_entity(&mut world, parent, true).is_ok());
+        assert!(world.get_entity(other_child).is_none());
     }
 }
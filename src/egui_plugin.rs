@@ -0,0 +1,78 @@
+use crate::app::{
+    add_pause_stages, count_frames, input_pause, run_command_args, scale_time, EnteringConsole,
+    FrameStep, Pause, PauseStages, TimeScale,
+};
+use bevy::{
+    ecs::{event::ManualEventReader, schedule::StageLabelId},
+    prelude::*,
+    time::TimeSystem,
+};
+use bevy_console::{ConsoleCommandEntered, FromValue, PrintConsoleLine};
+
+#[derive(Default)]
+struct DebugConsoleReader(ManualEventReader<ConsoleCommandEntered>);
+
+fn debug_console(world: &mut World) {
+    // collect the commands first so the events aren't borrowed while the
+    // commands run against the world
+    let commands: Vec<Vec<String>> =
+        world.resource_scope(|world, mut reader: Mut<DebugConsoleReader>| {
+            let events = world.resource::<Events<ConsoleCommandEntered>>();
+            reader
+                .0
+                .iter(events)
+                // bevy_console handles its own clear and exit commands
+                .filter(|event| event.command != "clear" && event.command != "exit")
+                .map(|event| {
+                    let mut args = vec![event.command.clone()];
+                    args.extend(
+                        event
+                            .args
+                            .iter()
+                            .filter_map(|x| String::from_value(x, 0).ok()),
+                    );
+                    args
+                })
+                .collect()
+        });
+
+    for command in commands {
+        let output = run_command_args(world, command);
+
+        // the console prints every event as a single line
+        let mut events = world.resource_mut::<Events<PrintConsoleLine>>();
+        for line in output.lines() {
+            events.send(PrintConsoleLine::new(line.to_string()));
+        }
+    }
+}
+
+/// Runs the debug console commands entered into bevy_console's egui console.
+/// Add `ConsolePlugin` from bevy_console as well.
+///
+/// `pause` skips the stages in `pause_stages`. bevy_console draws in
+/// `CoreStage::Update`, so unlike the other consoles `Update` can't be paused.
+/// Put the gameplay systems that should pause into their own stages and add
+/// those stages before this plugin. Without any `pause_stages`, `pause` and
+/// `step` report an error.
+#[derive(Default)]
+pub struct DebugConsoleEguiPlugin {
+    pub pause_stages: Vec<StageLabelId>,
+}
+
+impl Plugin for DebugConsoleEguiPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(Pause(false))
+            .insert_resource(PauseStages(self.pause_stages.clone()))
+            .insert_resource(EnteringConsole(false))
+            .init_resource::<FrameStep>()
+            .init_resource::<TimeScale>()
+            .add_system_to_stage(CoreStage::Last, count_frames)
+            .add_system_to_stage(CoreStage::First, scale_time.after(TimeSystem))
+            .init_resource::<DebugConsoleReader>()
+            .add_system(debug_console.exclusive_system())
+            .add_system(input_pause);
+
+        add_pause_stages(app, &self.pause_stages);
+    }
+}
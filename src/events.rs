@@ -0,0 +1,224 @@
+use crate::output::CommandOutput;
+use crate::reflect::print_reflect;
+use bevy::{
+    app::App as BevyApp,
+    ecs::{
+        event::{Event, Events, ManualEventReader},
+        world::{Mut, World},
+    },
+    reflect::{Reflect, TypeRegistry},
+    utils::get_short_name,
+};
+use clap::{arg, App, AppSettings, ArgMatches};
+use std::any::type_name;
+
+struct RegisteredEvent {
+    type_name: &'static str,
+    len: fn(&World) -> Option<usize>,
+    sent_this_frame: fn(&World) -> Option<usize>,
+    read: fn(&mut World, bool) -> Option<Vec<String>>,
+}
+
+/// The event types added with `add_console_event`, which `events read` can print.
+#[derive(Default)]
+pub struct ConsoleEvents {
+    events: Vec<RegisteredEvent>,
+}
+
+/// Remembers which events `events read --follow` already printed.
+struct EventFollower<T: Event>(ManualEventReader<T>);
+
+fn events_len<T: Event>(world: &World) -> Option<usize> {
+    world.get_resource::<Events<T>>().map(|events| events.len())
+}
+
+fn events_sent_this_frame<T: Event>(world: &World) -> Option<usize> {
+    world
+        .get_resource::<Events<T>>()
+        .map(|events| events.iter_current_update_events().count())
+}
+
+fn read_events<T: Event + Reflect>(world: &mut World, follow: bool) -> Option<Vec<String>> {
+    world.resource_scope(|world, mut follower: Mut<EventFollower<T>>| {
+        let events = world.get_resource::<Events<T>>()?;
+        let type_registry = world.resource::<TypeRegistry>().read();
+        let printed = if follow {
+            follower
+                .0
+                .iter(events)
+                .map(|event| print_reflect(&type_registry, event))
+                .collect()
+        } else {
+            events
+                .get_reader()
+                .iter(events)
+                .map(|event| print_reflect(&type_registry, event))
+                .collect()
+        };
+        Some(printed)
+    })
+}
+
+pub fn add_event<T: Event + Reflect>(app: &mut BevyApp) {
+    app.init_resource::<ConsoleEvents>()
+        .insert_resource(EventFollower::<T>(ManualEventReader::default()));
+
+    let mut console_events = app.world.resource_mut::<ConsoleEvents>();
+    console_events
+        .events
+        .retain(|event| event.type_name != type_name::<T>());
+    console_events.events.push(RegisteredEvent {
+        type_name: type_name::<T>(),
+        len: events_len::<T>,
+        sent_this_frame: events_sent_this_frame::<T>,
+        read: read_events::<T>,
+    });
+}
+
+fn find_registered_event<'a>(
+    console_events: &'a ConsoleEvents,
+    type_name: &str,
+) -> Option<&'a RegisteredEvent> {
+    console_events
+        .events
+        .iter()
+        .find(|event| event.type_name == type_name)
+}
+
+fn list_events(world: &World) -> String {
+    let mut output = String::new();
+
+    let components = world.components();
+    let mut event_types: Vec<&str> = world
+        .archetypes()
+        .resource()
+        .components()
+        .filter_map(|id| components.get_info(id))
+        .filter_map(|info| {
+            info.name()
+                .strip_prefix("bevy_ecs::event::Events<")
+                .and_then(|name| name.strip_suffix('>'))
+        })
+        .collect();
+    event_types.sort_unstable();
+
+    output.push_str("[buffered] [sent this frame] [event type]\n");
+    for event_type in event_types {
+        match world
+            .get_resource::<ConsoleEvents>()
+            .and_then(|console_events| find_registered_event(console_events, event_type))
+        {
+            Some(event) => output.push_str(&format!(
+                "{} {} {}\n",
+                (event.len)(world).unwrap_or_default(),
+                (event.sent_this_frame)(world).unwrap_or_default(),
+                event_type
+            )),
+            None => output.push_str(&format!(
+                "- - {} (not registered, add it with `add_console_event` to see its counts)\n",
+                event_type
+            )),
+        }
+    }
+
+    output
+}
+
+fn read_registered_events(world: &mut World, type_name: &str, follow: bool) -> CommandOutput {
+    let read = {
+        let console_events = match world.get_resource::<ConsoleEvents>() {
+            Some(console_events) => console_events,
+            None => {
+                return CommandOutput::Error(String::from(
+                    "No events registered, add them with `add_console_event`\n",
+                ))
+            }
+        };
+
+        let matches: Vec<&RegisteredEvent> = console_events
+            .events
+            .iter()
+            .filter(|event| {
+                event.type_name == type_name || get_short_name(event.type_name) == type_name
+            })
+            .collect();
+
+        match matches.as_slice() {
+            [] => {
+                return CommandOutput::Error(format!(
+                    "No registered event found with name {}\n",
+                    type_name
+                ))
+            }
+            [event] => event.read,
+            events => {
+                let mut output = String::new();
+                output.push_str(&format!(
+                    "More than one registered event found with name {}\n",
+                    type_name
+                ));
+                output.push_str("Consider using the full type name instead\n\n");
+                events
+                    .iter()
+                    .for_each(|event| output.push_str(&format!("{}\n", event.type_name)));
+                return CommandOutput::Error(output);
+            }
+        }
+    };
+
+    let printed = match read(world, follow) {
+        Some(printed) => printed,
+        None => {
+            return CommandOutput::Error(format!(
+                "No Events<{}> resource found, add it with `add_event`\n",
+                type_name
+            ))
+        }
+    };
+
+    let mut output = String::new();
+    output.push_str(&format!("events ({}):\n", printed.len()));
+    for (i, event) in printed.iter().enumerate() {
+        output.push_str(&format!("[{}] {}", i, event));
+    }
+
+    output.into()
+}
+
+pub fn build_commands<'a>(app: App<'a>) -> App<'a> {
+    let app = app.subcommand(
+        App::new("events")
+            .about("get info about events")
+            .alias("event")
+            .setting(AppSettings::SubcommandRequiredElseHelp)
+            .subcommand(App::new("list").about("list all event types and how many of the registered ones are buffered"))
+            .subcommand(
+                App::new("read")
+                    .about("print the buffered events of a type added with `add_console_event`")
+                    .args([
+                        arg!(<Type> "event type name"),
+                        arg!(--follow "only print the events sent since the last `read --follow`, reads once and returns"),
+                    ]),
+            ),
+    );
+
+    app
+}
+
+pub fn match_commands(matches: &ArgMatches, world: &mut World) -> Option<CommandOutput> {
+    let output = match matches.subcommand() {
+        Some(("events", matches)) => match matches.subcommand() {
+            Some(("list", _)) => list_events(world).into(),
+            Some(("read", matches)) => match matches.value_of("Type") {
+                Some(type_name) => {
+                    read_registered_events(world, type_name, matches.is_present("follow"))
+                }
+                None => String::from("this line should not be hittable").into(),
+            },
+            _ => String::from("this line should not be hittable").into(),
+        },
+        _ => return None,
+    };
+
+    Some(output)
+}
@@ -1,8 +1,31 @@
 mod app;
+mod command;
 mod ecs;
+#[cfg(feature = "bevy_console")]
+mod egui_plugin;
+mod events;
+mod output;
+mod profile;
 mod reflect;
+mod schedule;
 mod std_io_plugin;
+mod tcp_plugin;
 
-pub use crate::app::{build_commands, match_commands, Pause};
+pub use crate::app::{
+    build_commands, build_world_commands, match_commands, match_commands_output, run_command_args,
+    run_command_line, run_json_rpc, split_args, FrameStep, Pause, PauseStages,
+    TimeScale,
+};
+pub use crate::command::{AppConsoleExt, ConsoleCommand, ConsoleCommands};
+#[cfg(feature = "bevy_console")]
+pub use crate::egui_plugin::DebugConsoleEguiPlugin;
+pub use crate::output::{
+    ArchetypeInfo, ArchetypeSummary, CommandOutput, ComponentInfo, ComponentSummary, Counts,
+    EntityId, EntityInfo, EntitySummary,
+};
 pub use crate::std_io_plugin::ConsoleDebugPlugin;
-pub use crate::ecs::{get_archetype_id_by_entity_id};
+pub use crate::tcp_plugin::TcpConsolePlugin;
+#[allow(deprecated)]
+pub use crate::ecs::{
+    get_archetype_id, get_archetype_id_by_entity_id, get_entity_location, EntityArg,
+};
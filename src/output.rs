@@ -0,0 +1,193 @@
+use bevy::ecs::entity::Entity;
+use serde::Serialize;
+use std::fmt;
+
+/// The result of a console command. Renders as the console text through
+/// `Display` and serializes to json for tools.
+#[derive(Serialize)]
+#[serde(tag = "type", content = "data", rename_all = "snake_case")]
+pub enum CommandOutput {
+    /// Commands that don't have a structured result yet.
+    Text(String),
+    /// The command could not be completed, i.e. nothing was found.
+    Error(String),
+    Counts(Counts),
+    ArchetypeList(Vec<ArchetypeSummary>),
+    ArchetypeInfo(ArchetypeInfo),
+    ComponentList(Vec<ComponentSummary>),
+    ComponentInfo(Vec<ComponentInfo>),
+    EntityList(Vec<EntitySummary>),
+    EntityInfo(EntityInfo),
+    ResourceList(Vec<String>),
+}
+
+#[derive(Serialize, Clone, Copy)]
+pub struct EntityId {
+    pub index: u32,
+    pub generation: u32,
+}
+
+#[derive(Serialize)]
+pub struct Counts {
+    pub entities: u32,
+    pub components: usize,
+    pub archetypes: usize,
+}
+
+#[derive(Serialize)]
+pub struct ArchetypeSummary {
+    pub id: usize,
+    pub entity_count: usize,
+}
+
+#[derive(Serialize)]
+pub struct ArchetypeInfo {
+    pub id: usize,
+    pub table_id: usize,
+    pub entities: Vec<EntityId>,
+    pub table_components: Vec<ComponentSummary>,
+    pub sparse_set_components: Vec<ComponentSummary>,
+}
+
+#[derive(Serialize)]
+pub struct ComponentSummary {
+    pub id: usize,
+    pub name: String,
+}
+
+#[derive(Serialize)]
+pub struct ComponentInfo {
+    pub id: usize,
+    pub name: String,
+    pub storage_type: String,
+    pub send_and_sync: bool,
+}
+
+#[derive(Serialize)]
+pub struct EntitySummary {
+    pub entity: EntityId,
+    pub archetype_id: usize,
+}
+
+#[derive(Serialize)]
+pub struct EntityInfo {
+    pub entity: EntityId,
+    pub archetype_id: usize,
+    pub table_id: usize,
+    pub table_row: usize,
+    pub table_components: Vec<ComponentSummary>,
+    pub sparse_set_components: Vec<ComponentSummary>,
+}
+
+impl From<String> for CommandOutput {
+    fn from(text: String) -> Self {
+        CommandOutput::Text(text)
+    }
+}
+
+impl From<Result<String, String>> for CommandOutput {
+    fn from(result: Result<String, String>) -> Self {
+        match result {
+            Ok(text) => CommandOutput::Text(text),
+            Err(message) => CommandOutput::Error(message),
+        }
+    }
+}
+
+impl From<Entity> for EntityId {
+    fn from(entity: Entity) -> Self {
+        EntityId {
+            index: entity.id(),
+            generation: entity.generation(),
+        }
+    }
+}
+
+impl fmt::Display for EntityId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}v{}", self.index, self.generation)
+    }
+}
+
+fn write_components(
+    f: &mut fmt::Formatter<'_>,
+    label: &str,
+    components: &[ComponentSummary],
+) -> fmt::Result {
+    write!(f, "{} ({}): ", label, components.len())?;
+    for component in components {
+        write!(f, "{} {}, ", component.id, component.name)?;
+    }
+    writeln!(f)
+}
+
+impl fmt::Display for CommandOutput {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CommandOutput::Text(text) | CommandOutput::Error(text) => write!(f, "{}", text),
+            CommandOutput::Counts(counts) => writeln!(
+                f,
+                "entities: {}, components: {}, archetypes: {}",
+                counts.entities, counts.components, counts.archetypes
+            ),
+            CommandOutput::ArchetypeList(archetypes) => {
+                writeln!(f, "[id] [entity count]")?;
+                for archetype in archetypes {
+                    writeln!(f, "{} {}", archetype.id, archetype.entity_count)?;
+                }
+                Ok(())
+            }
+            CommandOutput::ArchetypeInfo(archetype) => {
+                writeln!(f, "id: ArchetypeId({})", archetype.id)?;
+                writeln!(f, "table_id: TableId({})", archetype.table_id)?;
+                write!(f, "entities ({}): ", archetype.entities.len())?;
+                for entity in &archetype.entities {
+                    write!(f, "{}, ", entity)?;
+                }
+                writeln!(f)?;
+                write_components(f, "table_components", &archetype.table_components)?;
+                write_components(f, "sparse set components", &archetype.sparse_set_components)
+            }
+            CommandOutput::ComponentList(components) => {
+                writeln!(f, "[component id] [component name]")?;
+                for component in components {
+                    writeln!(f, "{} {}", component.id, component.name)?;
+                }
+                Ok(())
+            }
+            CommandOutput::ComponentInfo(components) => {
+                for component in components {
+                    writeln!(f, "Name: {}", component.name)?;
+                    writeln!(f, "Id: {}", component.id)?;
+                    writeln!(f, "StorageType: {}", component.storage_type)?;
+                    writeln!(f, "SendAndSync: {}", component.send_and_sync)?;
+                    writeln!(f)?;
+                }
+                Ok(())
+            }
+            CommandOutput::EntityList(entities) => {
+                writeln!(f, "[entity] [archetype id]")?;
+                for entity in entities {
+                    writeln!(f, "{} {}", entity.entity, entity.archetype_id)?;
+                }
+                Ok(())
+            }
+            CommandOutput::EntityInfo(entity) => {
+                writeln!(f, "id: {}", entity.entity.index)?;
+                writeln!(f, "generation: {}", entity.entity.generation)?;
+                writeln!(f, "archetype_id: {}", entity.archetype_id)?;
+                writeln!(f, "table_id: TableId({})", entity.table_id)?;
+                writeln!(f, "table_row: {}", entity.table_row)?;
+                write_components(f, "table_components", &entity.table_components)?;
+                write_components(f, "sparse set components", &entity.sparse_set_components)
+            }
+            CommandOutput::ResourceList(resources) => {
+                writeln!(f, "[resource name]")?;
+                for resource in resources {
+                    writeln!(f, "{}", resource)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
@@ -0,0 +1,163 @@
+use crate::output::CommandOutput;
+use bevy::{ecs::world::World, utils::Duration};
+use clap::{arg, App, AppSettings, ArgMatches};
+use std::{cmp::Reverse, collections::HashMap};
+
+/// Wall clock times of the stages and systems recorded by `profile start`.
+#[derive(Default)]
+pub struct Profiler {
+    frames_remaining: u32,
+    frames: u32,
+    stages: HashMap<String, Vec<Duration>>,
+    systems: HashMap<String, Vec<Duration>>,
+}
+
+impl Profiler {
+    pub fn start(&mut self, frames: u32) {
+        self.frames_remaining = frames;
+        self.frames = 0;
+        self.stages.clear();
+        self.systems.clear();
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.frames_remaining > 0
+    }
+
+    pub fn record_frame(
+        &mut self,
+        stages: Vec<(String, Duration)>,
+        systems: Vec<(String, Duration)>,
+    ) {
+        for (name, time) in stages {
+            self.stages.entry(name).or_default().push(time);
+        }
+        for (name, time) in systems {
+            self.systems.entry(name).or_default().push(time);
+        }
+        self.frames += 1;
+        self.frames_remaining = self.frames_remaining.saturating_sub(1);
+    }
+}
+
+struct TimingStats<'a> {
+    name: &'a str,
+    count: usize,
+    mean: Duration,
+    p95: Duration,
+    max: Duration,
+}
+
+fn get_stats(samples: &HashMap<String, Vec<Duration>>) -> Vec<TimingStats<'_>> {
+    let mut stats: Vec<TimingStats> = samples
+        .iter()
+        .filter(|(_, times)| !times.is_empty())
+        .map(|(name, times)| {
+            let mut sorted = times.clone();
+            sorted.sort();
+            let p95_index = ((sorted.len() as f64 * 0.95).ceil() as usize).max(1) - 1;
+            TimingStats {
+                name,
+                count: sorted.len(),
+                mean: sorted.iter().sum::<Duration>() / sorted.len() as u32,
+                p95: sorted[p95_index],
+                max: sorted[sorted.len() - 1],
+            }
+        })
+        .collect();
+    stats.sort_by_key(|stats| Reverse(stats.mean));
+
+    stats
+}
+
+fn write_table(output: &mut String, title: &str, samples: &HashMap<String, Vec<Duration>>) {
+    let millis = |duration: Duration| duration.as_secs_f64() * 1000.0;
+
+    output.push_str(&format!("{}:\n", title));
+    output.push_str("[mean ms] [p95 ms] [max ms] [count] [name]\n");
+    for stats in get_stats(samples) {
+        output.push_str(&format!(
+            "{:.3} {:.3} {:.3} {} {}\n",
+            millis(stats.mean),
+            millis(stats.p95),
+            millis(stats.max),
+            stats.count,
+            stats.name
+        ));
+    }
+}
+
+fn print_report(profiler: &Profiler) -> CommandOutput {
+    if profiler.is_running() {
+        return format!(
+            "still profiling, {} of {} frames left\n",
+            profiler.frames_remaining,
+            profiler.frames + profiler.frames_remaining
+        )
+        .into();
+    }
+    if profiler.frames == 0 {
+        return CommandOutput::Error(String::from(
+            "No profile recorded, run `profile start` first\n",
+        ));
+    }
+
+    let mut output = String::new();
+    output.push_str(&format!("frames: {}\n", profiler.frames));
+    if !profiler.stages.is_empty() {
+        write_table(&mut output, "stages", &profiler.stages);
+    }
+    write_table(&mut output, "systems", &profiler.systems);
+    output.push_str(
+        "exclusive systems are not timed on their own, they only count towards their stage's time\n",
+    );
+    output.push_str("systems ran one after another while profiling, not in parallel\n");
+
+    output.into()
+}
+
+pub fn build_commands<'a>(app: App<'a>) -> App<'a> {
+    let app = app.subcommand(
+        App::new("profile")
+            .about("time the stages and systems over a number of frames")
+            .setting(AppSettings::SubcommandRequiredElseHelp)
+            .subcommand(
+                App::new("start")
+                    .about("record the next N frames")
+                    .arg(arg!([Frames] "number of frames to record").default_value("60")),
+            )
+            .subcommand(App::new("report").about("print the recorded times, slowest first")),
+    );
+
+    app
+}
+
+pub fn match_commands(matches: &ArgMatches, world: &mut World) -> Option<CommandOutput> {
+    let matches = match matches.subcommand() {
+        Some(("profile", matches)) => matches,
+        _ => return None,
+    };
+
+    let mut profiler = match world.get_resource_mut::<Profiler>() {
+        Some(profiler) => profiler,
+        None => {
+            return Some(CommandOutput::Error(String::from(
+                "No Profiler resource found, call `add_schedule_commands` after adding all systems\n",
+            )))
+        }
+    };
+
+    let output = match matches.subcommand() {
+        Some(("start", matches)) => match matches.value_of_t::<u32>("Frames") {
+            Ok(frames) if frames > 0 => {
+                profiler.start(frames);
+                format!("profiling the next {} frames, see `profile report`\n", frames).into()
+            }
+            _ => CommandOutput::Error(String::from("Frames must be at least 1\n")),
+        },
+        Some(("report", _)) => print_report(&profiler),
+        _ => String::from("this line should not be hittable").into(),
+    };
+
+    Some(output)
+}
@@ -1,25 +1,47 @@
-use bevy::reflect::TypeRegistry;
-use clap::{App, AppSettings, ArgMatches};
+use crate::output::CommandOutput;
+use bevy::{
+    ecs::reflect::{ReflectComponent, ReflectResource},
+    reflect::{
+        std_traits::ReflectDefault, GetPath, Reflect, ReflectDeserialize, ReflectRef,
+        ReflectSerialize, TypeInfo, TypeRegistration, TypeRegistry, TypeRegistryInternal,
+    },
+    utils::get_short_name,
+};
+use clap::{arg, App, AppSettings, ArgMatches};
 
 pub fn build_commands<'a>(app: App<'a>) -> App<'a> {
     let app = app.subcommand(
         App::new("reflect")
             .about("get reflection info")
             .setting(AppSettings::SubcommandRequiredElseHelp)
-            .subcommand(App::new("list").about("list all reflection types")),
+            .subcommand(App::new("list").about("list all reflection types"))
+            .subcommand(
+                App::new("info")
+                    .about("get the schema of a reflected type")
+                    .arg(arg!(<Type> "short or full name of the type")),
+            ),
     );
 
     app
 }
 
-pub fn match_commands(matches: &ArgMatches, reflect: &TypeRegistry) -> String {
-    match matches.subcommand() {
+pub fn match_commands(matches: &ArgMatches, reflect: &TypeRegistry) -> Option<CommandOutput> {
+    let output = match matches.subcommand() {
         Some(("reflect", matches)) => match matches.subcommand() {
-            Some(("list", _)) => list_reflection(reflect),
-            _ => String::from("this line should not be able to be run"),
+            Some(("list", _)) => list_reflection(reflect).into(),
+            Some(("info", matches)) => {
+                if let Some(type_name) = matches.value_of("Type") {
+                    print_type_info(reflect, type_name).into()
+                } else {
+                    String::from("this line should not be able to be run").into()
+                }
+            }
+            _ => String::from("this line should not be able to be run").into(),
         },
-        _ => String::from(""),
-    }
+        _ => return None,
+    };
+
+    Some(output)
 }
 
 fn list_reflection(reflect: &TypeRegistry) -> String {
@@ -33,3 +55,325 @@ fn list_reflection(reflect: &TypeRegistry) -> String {
 
     output
 }
+
+pub fn find_registration<'a>(
+    type_registry: &'a TypeRegistryInternal,
+    type_name: &str,
+) -> Result<&'a TypeRegistration, String> {
+    if let Some(registration) = type_registry
+        .get_with_name(type_name)
+        .or_else(|| type_registry.get_with_short_name(type_name))
+    {
+        return Ok(registration);
+    }
+
+    // `get_with_short_name` returns nothing when the short name is ambiguous
+    let matches: Vec<&str> = type_registry
+        .iter()
+        .filter(|registration| registration.short_name() == type_name)
+        .map(|registration| registration.type_name())
+        .collect();
+
+    if matches.is_empty() {
+        return Err(format!("No reflected type found with name {}\n", type_name));
+    }
+
+    let mut output = String::new();
+    output.push_str(&format!(
+        "More than one reflected type found with name {}\n",
+        type_name
+    ));
+    output.push_str("Consider using the full type name instead\n\n");
+    matches
+        .iter()
+        .for_each(|name| output.push_str(&format!("{}\n", name)));
+    Err(output)
+}
+
+fn print_type_info(reflect: &TypeRegistry, type_name: &str) -> Result<String, String> {
+    let type_registry = reflect.read();
+    let registration = find_registration(&type_registry, type_name)?;
+
+    let mut output = String::new();
+    output.push_str(&format!("name: {}\n", registration.type_name()));
+
+    match registration.type_info() {
+        TypeInfo::Struct(info) => {
+            output.push_str("kind: struct\n");
+            output.push_str(&format!("fields ({}):\n", info.field_len()));
+            info.iter().for_each(|field| {
+                output.push_str(&format!("  {}: {}\n", field.name(), field.type_name()))
+            });
+        }
+        TypeInfo::TupleStruct(info) => {
+            output.push_str("kind: tuple struct\n");
+            output.push_str(&format!("fields ({}):\n", info.field_len()));
+            info.iter().for_each(|field| {
+                output.push_str(&format!("  {}: {}\n", field.index(), field.type_name()))
+            });
+        }
+        TypeInfo::Tuple(info) => {
+            output.push_str("kind: tuple\n");
+            output.push_str(&format!("fields ({}):\n", info.field_len()));
+            info.iter().for_each(|field| {
+                output.push_str(&format!("  {}: {}\n", field.index(), field.type_name()))
+            });
+        }
+        TypeInfo::List(info) => {
+            output.push_str("kind: list\n");
+            output.push_str(&format!("item: {}\n", info.item_type_name()));
+        }
+        TypeInfo::Array(info) => {
+            output.push_str("kind: array\n");
+            output.push_str(&format!("item: {}\n", info.item_type_name()));
+            output.push_str(&format!("capacity: {}\n", info.capacity()));
+        }
+        TypeInfo::Map(info) => {
+            output.push_str("kind: map\n");
+            output.push_str(&format!("key: {}\n", info.key_type_name()));
+            output.push_str(&format!("value: {}\n", info.value_type_name()));
+        }
+        // bevy 0.8 reflects enums as opaque values, so their variants can't be listed
+        TypeInfo::Value(_) => output.push_str("kind: value\n"),
+        TypeInfo::Dynamic(_) => output.push_str("kind: dynamic\n"),
+    }
+
+    output.push_str("type data:\n");
+    let type_data = [
+        ("ReflectComponent", registration.data::<ReflectComponent>().is_some()),
+        ("ReflectResource", registration.data::<ReflectResource>().is_some()),
+        ("ReflectDefault", registration.data::<ReflectDefault>().is_some()),
+        ("ReflectSerialize", registration.data::<ReflectSerialize>().is_some()),
+        ("ReflectDeserialize", registration.data::<ReflectDeserialize>().is_some()),
+    ];
+    type_data
+        .iter()
+        .for_each(|(name, registered)| output.push_str(&format!("  {}: {}\n", name, registered)));
+
+    Ok(output)
+}
+
+/// Parses `value` as plain RON, i.e. `(x: 1.0)`, into an instance of the
+/// registered type. Only types that register `ReflectDeserialize` can be parsed.
+pub fn deserialize_ron(
+    registration: &TypeRegistration,
+    value: &str,
+) -> Result<Box<dyn Reflect>, String> {
+    // bevy's untyped `ReflectDeserializer` makes ron panic on some inputs, i.e.
+    // `(value: 2.0)`, so user input only goes to the type's own deserializer
+    let reflect_deserialize = registration.data::<ReflectDeserialize>().ok_or_else(|| {
+        format!(
+            "{} does not reflect Deserialize, so it can't be parsed from a value\n",
+            registration.short_name()
+        )
+    })?;
+
+    let mut deserializer = ron::de::Deserializer::from_str(value)
+        .map_err(|err| format!("could not parse value: {}\n", err))?;
+
+    reflect_deserialize
+        .deserialize(&mut deserializer)
+        .map_err(|err| {
+            format!(
+                "could not parse value as {}: {}\n",
+                registration.short_name(),
+                err
+            )
+        })
+}
+
+/// Pretty prints a reflected value, walking into structs, tuples, lists and
+/// maps. Leaf values are printed as RON when their type registers
+/// `ReflectSerialize`, otherwise with their `Debug` output.
+pub fn print_reflect(type_registry: &TypeRegistryInternal, value: &dyn Reflect) -> String {
+    let mut output = String::new();
+    write_reflect(&mut output, type_registry, value, 0);
+
+    output
+}
+
+fn write_reflect(
+    output: &mut String,
+    type_registry: &TypeRegistryInternal,
+    value: &dyn Reflect,
+    depth: usize,
+) {
+    let indent = "  ".repeat(depth + 1);
+    match value.reflect_ref() {
+        ReflectRef::Struct(s) => {
+            output.push_str(&format!("{}\n", get_short_name(s.type_name())));
+            for i in 0..s.field_len() {
+                if let (Some(name), Some(field)) = (s.name_at(i), s.field_at(i)) {
+                    output.push_str(&format!("{}{}: ", indent, name));
+                    write_reflect(output, type_registry, field, depth + 1);
+                }
+            }
+        }
+        ReflectRef::TupleStruct(s) => {
+            output.push_str(&format!("{}\n", get_short_name(s.type_name())));
+            for (i, field) in s.iter_fields().enumerate() {
+                output.push_str(&format!("{}{}: ", indent, i));
+                write_reflect(output, type_registry, field, depth + 1);
+            }
+        }
+        ReflectRef::Tuple(t) => {
+            output.push_str(&format!("{}\n", get_short_name(t.type_name())));
+            for (i, field) in t.iter_fields().enumerate() {
+                output.push_str(&format!("{}{}: ", indent, i));
+                write_reflect(output, type_registry, field, depth + 1);
+            }
+        }
+        ReflectRef::List(l) => {
+            output.push_str(&format!("{} ({})\n", get_short_name(l.type_name()), l.len()));
+            for (i, item) in l.iter().enumerate() {
+                output.push_str(&format!("{}[{}]: ", indent, i));
+                write_reflect(output, type_registry, item, depth + 1);
+            }
+        }
+        ReflectRef::Array(a) => {
+            output.push_str(&format!("{} ({})\n", get_short_name(a.type_name()), a.len()));
+            for (i, item) in a.iter().enumerate() {
+                output.push_str(&format!("{}[{}]: ", indent, i));
+                write_reflect(output, type_registry, item, depth + 1);
+            }
+        }
+        ReflectRef::Map(m) => {
+            output.push_str(&format!("{} ({})\n", get_short_name(m.type_name()), m.len()));
+            for (key, item) in m.iter() {
+                output.push_str(&format!("{}{:?}: ", indent, key));
+                write_reflect(output, type_registry, item, depth + 1);
+            }
+        }
+        // enums and other opaque types are reflected as values in bevy 0.8
+        ReflectRef::Value(v) => output.push_str(&format!("{}\n", print_value(type_registry, v))),
+    }
+}
+
+fn print_value(type_registry: &TypeRegistryInternal, value: &dyn Reflect) -> String {
+    // the `Debug` output of many values is only `Reflect(type name)`, i.e. for
+    // `Entity`, so the serialized value is preferred
+    type_registry
+        .get(value.type_id())
+        .and_then(|registration| registration.data::<ReflectSerialize>())
+        .and_then(|reflect_serialize| {
+            ron::to_string(reflect_serialize.get_serializable(value).borrow()).ok()
+        })
+        .unwrap_or_else(|| format!("{:?}", value))
+}
+
+/// Sets the field at `path` inside of `root` to `value`. The value is parsed
+/// according to the reflected type of the field. An empty path sets `root`.
+pub fn set_reflect_path(root: &mut dyn Reflect, path: &str, value: &str) -> Result<(), String> {
+    let target = if path.is_empty() {
+        root
+    } else {
+        root.path_mut(path)
+            .map_err(|err| format!("invalid path '{}': {}\n", path, err))?
+    };
+
+    let new_value = parse_reflect_value(target, value)?;
+    target
+        .set(new_value)
+        .map_err(|_| format!("could not set value of type {}\n", target.type_name()))
+}
+
+macro_rules! parse_as {
+    ($target:expr, $value:expr, $($ty:ty),*) => {
+        $(
+            if $target.is::<$ty>() {
+                return $value
+                    .parse::<$ty>()
+                    .map(|parsed| Box::new(parsed) as Box<dyn Reflect>)
+                    .map_err(|err| {
+                        format!("could not parse '{}' as {}: {}\n", $value, stringify!($ty), err)
+                    });
+            }
+        )*
+    };
+}
+
+fn parse_reflect_value(target: &dyn Reflect, value: &str) -> Result<Box<dyn Reflect>, String> {
+    parse_as!(target, value, f32, f64, i8, i16, i32, i64, isize, u8, u16, u32, u64, usize, bool);
+
+    if target.is::<String>() {
+        let value = value
+            .strip_prefix('"')
+            .and_then(|value| value.strip_suffix('"'))
+            .unwrap_or(value);
+        return Ok(Box::new(value.to_string()));
+    }
+
+    Err(format!(
+        "setting values of type {} is not supported, try setting one of its fields instead\n",
+        target.type_name()
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy::{ecs::entity::Entity, reflect::GetTypeRegistration};
+    use std::borrow::Cow;
+    use serde::Deserialize;
+
+    #[derive(Reflect, Deserialize)]
+    #[reflect(Deserialize)]
+    struct Speed {
+        value: f32,
+    }
+
+    #[derive(Reflect)]
+    struct Health {
+        value: f32,
+    }
+
+    #[derive(Reflect)]
+    struct Target {
+        entity: Entity,
+        label: Cow<'static, str>,
+    }
+
+    fn deserialize<T: GetTypeRegistration>(value: &str) -> Result<Box<dyn Reflect>, String> {
+        deserialize_ron(&T::get_type_registration(), value)
+    }
+
+    #[test]
+    fn deserialize_ron_reads_plain_ron() {
+        let speed = deserialize::<Speed>("(value: 2.0)").unwrap();
+        assert_eq!(speed.downcast_ref::<Speed>().unwrap().value, 2.0);
+    }
+
+    #[test]
+    fn deserialize_ron_needs_reflect_deserialize() {
+        // this used to panic inside of ron
+        let err = deserialize::<Health>("(value: 2.0)").err().unwrap();
+        assert!(err.contains("does not reflect Deserialize"), "{}", err);
+    }
+
+    #[test]
+    fn deserialize_ron_rejects_values_of_another_type() {
+        assert!(deserialize::<Speed>("2.0").is_err());
+        assert!(deserialize::<Speed>("(speed: 2.0)").is_err());
+        assert!(deserialize::<Speed>("(value: \"fast\")").is_err());
+        assert!(deserialize::<Speed>("(value").is_err());
+    }
+
+    #[test]
+    fn print_reflect_serializes_registered_values() {
+        let mut type_registry = TypeRegistryInternal::new();
+        type_registry.register::<Entity>();
+        type_registry.register::<Cow<'static, str>>();
+        let target = Target {
+            entity: Entity::from_raw(3),
+            label: Cow::from("boss"),
+        };
+
+        let output = print_reflect(&type_registry, &target);
+        assert!(output.contains("entity: 3\n"), "{}", output);
+        assert!(output.contains("label: \"boss\"\n"), "{}", output);
+
+        // without the registrations only the type names are known
+        let output = print_reflect(&TypeRegistryInternal::empty(), &target);
+        assert!(output.contains("Reflect("), "{}", output);
+    }
+}
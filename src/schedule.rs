@@ -0,0 +1,631 @@
+use crate::output::CommandOutput;
+use crate::profile::Profiler;
+use bevy::{
+    app::App as BevyApp,
+    ecs::{
+        component::ComponentId,
+        query::Access,
+        schedule::{
+            GraphNode, ParallelExecutor, ParallelSystemContainer, ParallelSystemExecutor, Schedule,
+            SingleThreadedExecutor, Stage, StageLabel, StageLabelId, SystemContainer, SystemStage,
+        },
+        world::World,
+    },
+    log::warn,
+    utils::{Duration, Instant},
+};
+use clap::{arg, App, AppSettings, ArgMatches};
+use std::{collections::HashSet, fmt::Debug};
+
+#[derive(StageLabel)]
+struct ConsoleScheduleStage;
+
+/// The app schedule moved into a single stage by `add_schedule_commands`, so
+/// that it can be described to the console while the app is running.
+struct ConsoleSchedule {
+    schedule: Schedule,
+    system_count: Option<usize>,
+    disabled: HashSet<String>,
+    profiling: bool,
+}
+
+impl Stage for ConsoleSchedule {
+    fn run(&mut self, world: &mut World) {
+        let disabled = &world.resource::<DisabledSystems>().0;
+        let profiling = world.resource::<Profiler>().is_running();
+        if *disabled != self.disabled || profiling != self.profiling {
+            self.disabled = disabled.clone();
+            self.profiling = profiling;
+            apply_executors(&mut self.schedule, &self.disabled, profiling);
+        }
+
+        if profiling {
+            let stages = run_stages_timed(&mut self.schedule, world);
+            let systems = take_system_timings(&mut self.schedule);
+            world.resource_mut::<Profiler>().record_frame(stages, systems);
+        } else {
+            self.schedule.run(world);
+        }
+
+        // systems are only sorted and initialized once their stage has run, so
+        // describe the schedule after running it and again if systems are added
+        let system_count = count_systems(&self.schedule);
+        if self.system_count != Some(system_count) {
+            world.insert_resource(describe_schedule(&self.schedule));
+            self.system_count = Some(system_count);
+        }
+    }
+}
+
+/// Wraps the app schedule so the `schedule` commands can read it. Needs to be
+/// called after all stages and systems are added: the app stages are moved out
+/// of `app.schedule`, so adding a system to them afterwards panics and stages
+/// added afterwards run outside of the console schedule.
+pub fn wrap_schedule(app: &mut BevyApp) {
+    assert!(
+        app.schedule
+            .get_stage::<ConsoleSchedule>(&ConsoleScheduleStage)
+            .is_none(),
+        "add_schedule_commands was called more than once"
+    );
+
+    let schedule = std::mem::take(&mut app.schedule);
+    app.schedule = Schedule::default().with_stage(
+        ConsoleScheduleStage,
+        ConsoleSchedule {
+            schedule,
+            system_count: None,
+            disabled: HashSet::new(),
+            profiling: false,
+        },
+    );
+    app.init_resource::<DisabledSystems>().init_resource::<Profiler>();
+
+    // warn about stages added after wrapping once the app is run
+    let runner = std::mem::replace(&mut app.runner, Box::new(|_| {}));
+    app.set_runner(move |app| {
+        for (label, _) in app.schedule.iter_stages() {
+            if label.as_label() != ConsoleScheduleStage.as_label() {
+                warn!(
+                    "stage {:?} was added after add_schedule_commands, the schedule commands can't see it",
+                    label
+                );
+            }
+        }
+        runner(app);
+    });
+}
+
+/// The names of the systems turned off with `systems disable`.
+#[derive(Default)]
+pub struct DisabledSystems(pub HashSet<String>);
+
+/// Runs the systems of a stage one after another like `SingleThreadedExecutor`,
+/// skipping the disabled ones and timing each system while profiling.
+///
+/// bevy can't add a run criterion to a system that is already in a stage and
+/// its executors can't skip single systems, so a stage with disabled systems
+/// runs single threaded until they are all enabled again. The stage's own
+/// executor is kept in `original` and put back then.
+struct ConsoleExecutor {
+    original: Box<dyn ParallelSystemExecutor>,
+    disabled: HashSet<String>,
+    timings: Option<Vec<(String, Duration)>>,
+}
+
+impl ParallelSystemExecutor for ConsoleExecutor {
+    fn rebuild_cached_data(&mut self, systems: &[ParallelSystemContainer]) {
+        self.original.rebuild_cached_data(systems);
+    }
+
+    fn run_systems(&mut self, systems: &mut [ParallelSystemContainer], world: &mut World) {
+        for container in systems {
+            if container.should_run() && !self.disabled.contains(container.name().as_ref()) {
+                let start = Instant::now();
+                container.system_mut().run((), world);
+                if let Some(timings) = &mut self.timings {
+                    timings.push((container.name().to_string(), start.elapsed()));
+                }
+            }
+        }
+    }
+}
+
+/// Takes the executor out of a stage so it can be put back later. Only the
+/// executors that come with bevy can be taken.
+fn take_executor(stage: &mut SystemStage) -> Option<Box<dyn ParallelSystemExecutor>> {
+    if let Some(executor) = stage.get_executor_mut::<ParallelExecutor>() {
+        return Some(Box::new(std::mem::take(executor)));
+    }
+    if let Some(executor) = stage.get_executor_mut::<SingleThreadedExecutor>() {
+        return Some(Box::new(std::mem::take(executor)));
+    }
+
+    None
+}
+
+fn stage_labels(schedule: &Schedule) -> Vec<StageLabelId> {
+    schedule
+        .iter_stages()
+        .map(|(label, _)| label.as_label())
+        .collect()
+}
+
+fn apply_executors(schedule: &mut Schedule, disabled: &HashSet<String>, profiling: bool) {
+    for label in stage_labels(schedule) {
+        let stage = match schedule.get_stage_mut::<SystemStage>(&label) {
+            Some(stage) => stage,
+            None => continue,
+        };
+
+        let stage_disabled: HashSet<String> = stage
+            .parallel_systems()
+            .iter()
+            .map(|container| GraphNode::name(container).to_string())
+            .filter(|name| disabled.contains(name))
+            .collect();
+        let needs_console_executor = profiling || !stage_disabled.is_empty();
+        let timings = if profiling { Some(Vec::new()) } else { None };
+
+        if let Some(executor) = stage.get_executor_mut::<ConsoleExecutor>() {
+            if needs_console_executor {
+                executor.disabled = stage_disabled;
+                executor.timings = timings;
+            } else {
+                let original = std::mem::replace(
+                    &mut executor.original,
+                    Box::new(SingleThreadedExecutor),
+                );
+                stage.set_executor(original);
+            }
+        } else if needs_console_executor {
+            match take_executor(stage) {
+                Some(original) => stage.set_executor(Box::new(ConsoleExecutor {
+                    original,
+                    disabled: stage_disabled,
+                    timings,
+                })),
+                None => warn!(
+                    "stage {:?} has a custom executor, its systems can't be disabled or profiled",
+                    label
+                ),
+            }
+        }
+    }
+}
+
+/// Runs the stages one at a time like `Schedule::run_once` and times them.
+/// Stages that aren't a `SystemStage` or `Schedule` can't be run one at a
+/// time, then the schedule runs as usual without stage timings.
+fn run_stages_timed(schedule: &mut Schedule, world: &mut World) -> Vec<(String, Duration)> {
+    let can_time_stages = schedule
+        .iter_stages()
+        .all(|(_, stage)| stage.is::<SystemStage>() || stage.is::<Schedule>());
+    if !can_time_stages {
+        schedule.run(world);
+        return Vec::new();
+    }
+
+    let mut timings = Vec::new();
+    for label in stage_labels(schedule) {
+        let start = Instant::now();
+        if let Some(stage) = schedule.get_stage_mut::<SystemStage>(&label) {
+            stage.run(world);
+        } else if let Some(stage) = schedule.get_stage_mut::<Schedule>(&label) {
+            stage.run(world);
+        }
+        timings.push((format!("{:?}", label), start.elapsed()));
+    }
+
+    timings
+}
+
+fn take_system_timings(schedule: &mut Schedule) -> Vec<(String, Duration)> {
+    let mut timings = Vec::new();
+    for label in stage_labels(schedule) {
+        if let Some(executor) = schedule
+            .get_stage_mut::<SystemStage>(&label)
+            .and_then(|stage| stage.get_executor_mut::<ConsoleExecutor>())
+        {
+            timings.extend(executor.timings.iter_mut().flat_map(std::mem::take));
+        }
+    }
+
+    timings
+}
+
+pub struct ScheduleInfo {
+    pub stages: Vec<StageInfo>,
+}
+
+pub struct StageInfo {
+    pub label: String,
+    /// `None` for stages that aren't a `SystemStage`
+    pub systems: Option<Vec<SystemInfo>>,
+}
+
+pub struct SystemInfo {
+    pub name: String,
+    pub kind: &'static str,
+    pub labels: Vec<String>,
+    pub before: Vec<String>,
+    pub after: Vec<String>,
+    pub run_criteria: Option<String>,
+    /// `None` for exclusive systems, which can access anything
+    pub access: Option<Access<ComponentId>>,
+}
+
+fn describe_schedule(schedule: &Schedule) -> ScheduleInfo {
+    let mut stages = Vec::new();
+    describe_stages(&mut stages, schedule, "");
+
+    ScheduleInfo { stages }
+}
+
+fn describe_stages(stages: &mut Vec<StageInfo>, schedule: &Schedule, prefix: &str) {
+    for (label, stage) in schedule.iter_stages() {
+        let label = format!("{}{:?}", prefix, label);
+        if let Some(system_stage) = stage.downcast_ref::<SystemStage>() {
+            let mut systems = Vec::new();
+            describe_systems(
+                &mut systems,
+                "exclusive at start",
+                system_stage.exclusive_at_start_systems(),
+            );
+            describe_systems(&mut systems, "parallel", system_stage.parallel_systems());
+            describe_systems(
+                &mut systems,
+                "exclusive before commands",
+                system_stage.exclusive_before_commands_systems(),
+            );
+            describe_systems(
+                &mut systems,
+                "exclusive at end",
+                system_stage.exclusive_at_end_systems(),
+            );
+            stages.push(StageInfo {
+                label,
+                systems: Some(systems),
+            });
+        } else if let Some(schedule) = stage.downcast_ref::<Schedule>() {
+            // i.e. the startup stages are a schedule nested in the app schedule
+            describe_stages(stages, schedule, &format!("{}/", label));
+        } else {
+            stages.push(StageInfo {
+                label,
+                systems: None,
+            });
+        }
+    }
+}
+
+fn describe_systems<C: SystemContainer>(
+    systems: &mut Vec<SystemInfo>,
+    kind: &'static str,
+    containers: &[C],
+) {
+    for container in containers {
+        systems.push(SystemInfo {
+            name: GraphNode::name(container).to_string(),
+            kind,
+            labels: label_names(container.labels()),
+            before: label_names(container.before()),
+            after: label_names(container.after()),
+            run_criteria: container
+                .run_criteria_label()
+                .map(|label| format!("{:?}", label))
+                .or_else(|| container.run_criteria().map(|_| String::from("(unlabeled)"))),
+            access: container.component_access().cloned(),
+        });
+    }
+}
+
+fn count_systems(schedule: &Schedule) -> usize {
+    schedule
+        .iter_stages()
+        .map(|(_, stage)| {
+            if let Some(stage) = stage.downcast_ref::<SystemStage>() {
+                stage.exclusive_at_start_systems().len()
+                    + stage.parallel_systems().len()
+                    + stage.exclusive_before_commands_systems().len()
+                    + stage.exclusive_at_end_systems().len()
+            } else if let Some(schedule) = stage.downcast_ref::<Schedule>() {
+                count_systems(schedule)
+            } else {
+                0
+            }
+        })
+        .sum()
+}
+
+fn label_names<L: Debug>(labels: &[L]) -> Vec<String> {
+    labels.iter().map(|label| format!("{:?}", label)).collect()
+}
+
+pub fn get_schedule_info(world: &World) -> Result<&ScheduleInfo, String> {
+    world.get_resource::<ScheduleInfo>().ok_or_else(|| {
+        String::from(
+            "No schedule information found, call `add_schedule_commands` after adding all systems\n",
+        )
+    })
+}
+
+/// Nested stages are listed as `outer/inner` and labels like `CoreStage::Update`,
+/// so `Update` or the inner stage alone are enough to pick a stage.
+fn stage_matches(label: &str, name: &str) -> bool {
+    let last = label.rsplit('/').next().unwrap_or(label);
+    let short = last.rsplit("::").next().unwrap_or(last);
+    [label, last, short]
+        .iter()
+        .any(|part| part.eq_ignore_ascii_case(name))
+}
+
+/// Finds the parallel system with the full `name` or a name ending in `::name`.
+fn find_system(info: &ScheduleInfo, name: &str) -> Result<String, String> {
+    let suffix = format!("::{}", name);
+    let mut matches: Vec<(&StageInfo, &SystemInfo)> = info
+        .stages
+        .iter()
+        .flat_map(|stage| stage.systems.iter().flatten().map(move |system| (stage, system)))
+        .filter(|(_, system)| system.name == name || system.name.ends_with(&suffix))
+        .collect();
+    // prefer the copy of a system in a top level stage, which can be disabled
+    matches.sort_by(|(a_stage, a), (b_stage, b)| {
+        (&a.name, is_nested(a_stage)).cmp(&(&b.name, is_nested(b_stage)))
+    });
+    matches.dedup_by(|(_, a), (_, b)| a.name == b.name);
+
+    match matches.as_slice() {
+        [] => Err(format!("No system found with name {}\n", name)),
+        [(_, system)] if system.kind != "parallel" => Err(format!(
+            "{} is an exclusive system, only parallel systems can be disabled\n",
+            system.name
+        )),
+        [(stage, system)] if is_nested(stage) => Err(format!(
+            "{} runs in the nested stage {}, only systems of top level stages can be disabled\n",
+            system.name, stage.label
+        )),
+        [(_, system)] => Ok(system.name.clone()),
+        systems => {
+            let mut output = String::new();
+            output.push_str(&format!("More than one system found with name {}\n", name));
+            output.push_str("Consider using the full system name instead\n\n");
+            systems
+                .iter()
+                .for_each(|(_, system)| output.push_str(&format!("{}\n", system.name)));
+            Err(output)
+        }
+    }
+}
+
+/// Stages of a nested schedule, i.e. the startup stages, are listed as
+/// `outer/inner` and their executors aren't replaced.
+fn is_nested(stage: &StageInfo) -> bool {
+    stage.label.contains('/')
+}
+
+fn set_system_enabled(world: &mut World, name: &str, enabled: bool) -> CommandOutput {
+    let name = match get_schedule_info(world).and_then(|info| find_system(info, name)) {
+        Ok(name) => name,
+        Err(output) => return CommandOutput::Error(output),
+    };
+
+    let mut disabled = world.resource_mut::<DisabledSystems>();
+    if enabled {
+        disabled.0.remove(&name);
+        format!("enabled {}\n", name).into()
+    } else {
+        disabled.0.insert(name.clone());
+        format!("disabled {}, it is skipped from the next frame on\n", name).into()
+    }
+}
+
+fn list_system_states(
+    info: &ScheduleInfo,
+    disabled: &HashSet<String>,
+    only_disabled: bool,
+) -> String {
+    let mut output = String::new();
+
+    output.push_str("[stage] [system]\n");
+    for stage in &info.stages {
+        for system in stage.systems.iter().flatten() {
+            let is_disabled = disabled.contains(&system.name);
+            if is_disabled {
+                output.push_str(&format!("{} {} (disabled)\n", stage.label, system.name));
+            } else if !only_disabled {
+                output.push_str(&format!("{} {}\n", stage.label, system.name));
+            }
+        }
+    }
+
+    output
+}
+
+fn list_stages(info: &ScheduleInfo) -> String {
+    let mut output = String::new();
+
+    output.push_str("[stage] [system count]\n");
+    for stage in &info.stages {
+        match &stage.systems {
+            Some(systems) => output.push_str(&format!("{} {}\n", stage.label, systems.len())),
+            None => output.push_str(&format!("{} (not a SystemStage)\n", stage.label)),
+        }
+    }
+
+    output
+}
+
+fn write_labels(output: &mut String, name: &str, labels: &[String]) {
+    if !labels.is_empty() {
+        output.push_str(&format!("    {}: {}\n", name, labels.join(", ")));
+    }
+}
+
+fn list_systems(info: &ScheduleInfo, stage_name: Option<&str>) -> CommandOutput {
+    let stages: Vec<&StageInfo> = info
+        .stages
+        .iter()
+        .filter(|stage| stage_name.map_or(true, |name| stage_matches(&stage.label, name)))
+        .collect();
+
+    if stages.is_empty() {
+        return CommandOutput::Error(format!(
+            "No stage found with name {}\n",
+            stage_name.unwrap_or_default()
+        ));
+    }
+
+    let mut output = String::new();
+    for stage in stages {
+        output.push_str(&format!("{}:\n", stage.label));
+        let systems = match &stage.systems {
+            Some(systems) => systems,
+            None => {
+                output.push_str("  (not a SystemStage)\n");
+                continue;
+            }
+        };
+        for system in systems {
+            output.push_str(&format!("  [{}] {}\n", system.kind, system.name));
+            write_labels(&mut output, "labels", &system.labels);
+            write_labels(&mut output, "before", &system.before);
+            write_labels(&mut output, "after", &system.after);
+            if let Some(run_criteria) = &system.run_criteria {
+                output.push_str(&format!("    run criteria: {}\n", run_criteria));
+            }
+        }
+    }
+
+    output.into()
+}
+
+pub fn build_commands<'a>(app: App<'a>) -> App<'a> {
+    let app = app.subcommand(
+        App::new("schedule")
+            .about("get info about the stages and systems in the schedule")
+            .setting(AppSettings::SubcommandRequiredElseHelp)
+            .subcommand(App::new("stages").about("list the stages in the order they run"))
+            .subcommand(
+                App::new("systems")
+                    .about("list the systems in each stage with their labels and ordering")
+                    .arg(arg!(--stage [Stage] "only list the systems of this stage")),
+            ),
+    )
+    .subcommand(
+        App::new("systems")
+            .about("turn individual systems off and on")
+            .setting(AppSettings::SubcommandRequiredElseHelp)
+            .subcommand(
+                App::new("list")
+                    .about("list all systems and whether they are disabled")
+                    .arg(arg!(--disabled "only list the disabled systems")),
+            )
+            .subcommand(
+                App::new("disable")
+                    .about("skip a system until it is enabled again")
+                    .arg(arg!(<Name> "system name, i.e. move_player")),
+            )
+            .subcommand(
+                App::new("enable")
+                    .about("run a disabled system again")
+                    .arg(arg!(<Name> "system name, i.e. move_player")),
+            ),
+    );
+
+    app
+}
+
+pub fn match_commands(matches: &ArgMatches, world: &mut World) -> Option<CommandOutput> {
+    let output = match matches.subcommand() {
+        Some(("schedule", matches)) => {
+            let info = match get_schedule_info(world) {
+                Ok(info) => info,
+                Err(output) => return Some(CommandOutput::Error(output)),
+            };
+            match matches.subcommand() {
+                Some(("stages", _)) => list_stages(info).into(),
+                Some(("systems", matches)) => list_systems(info, matches.value_of("stage")),
+                _ => String::from("this line should not be hittable").into(),
+            }
+        }
+        Some(("systems", matches)) => match matches.subcommand() {
+            Some(("list", matches)) => {
+                let info = match get_schedule_info(world) {
+                    Ok(info) => info,
+                    Err(output) => return Some(CommandOutput::Error(output)),
+                };
+                let disabled = &world.resource::<DisabledSystems>().0;
+                list_system_states(info, disabled, matches.is_present("disabled")).into()
+            }
+            Some(("disable", matches)) => match matches.value_of("Name") {
+                Some(name) => set_system_enabled(world, name, false),
+                None => String::from("this line should not be hittable").into(),
+            },
+            Some(("enable", matches)) => match matches.value_of("Name") {
+                Some(name) => set_system_enabled(world, name, true),
+                None => String::from("this line should not be hittable").into(),
+            },
+            _ => String::from("this line should not be hittable").into(),
+        },
+        _ => return None,
+    };
+
+    Some(output)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stage_matches_the_label_or_its_last_part() {
+        assert!(stage_matches("CoreStage::Update", "CoreStage::Update"));
+        assert!(stage_matches("CoreStage::Update", "update"));
+        assert!(stage_matches("StartupSchedule/StartupStage::Startup", "StartupStage::Startup"));
+        assert!(stage_matches("StartupSchedule/StartupStage::Startup", "Startup"));
+        assert!(!stage_matches("CoreStage::PostUpdate", "Update"));
+        assert!(!stage_matches("CoreStage::Update", "CoreStage"));
+    }
+
+    fn system(name: &str, kind: &'static str) -> SystemInfo {
+        SystemInfo {
+            name: String::from(name),
+            kind,
+            labels: Vec::new(),
+            before: Vec::new(),
+            after: Vec::new(),
+            run_criteria: None,
+            access: None,
+        }
+    }
+
+    #[test]
+    fn find_system_only_finds_parallel_systems_of_top_level_stages() {
+        let info = ScheduleInfo {
+            stages: vec![
+                StageInfo {
+                    label: String::from("StartupSchedule/StartupStage::Startup"),
+                    systems: Some(vec![system("game::setup", "parallel")]),
+                },
+                StageInfo {
+                    label: String::from("CoreStage::Update"),
+                    systems: Some(vec![
+                        system("game::move_player", "parallel"),
+                        system("game::save", "exclusive at end"),
+                        system("ui::move_player", "parallel"),
+                    ]),
+                },
+            ],
+        };
+
+        assert_eq!(
+            find_system(&info, "game::move_player"),
+            Ok(String::from("game::move_player"))
+        );
+        assert!(find_system(&info, "move_player").is_err());
+        assert!(find_system(&info, "setup").is_err());
+        assert!(find_system(&info, "save").is_err());
+        assert!(find_system(&info, "jump").is_err());
+    }
+}
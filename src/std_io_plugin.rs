@@ -1,40 +1,22 @@
-use crate::app::{build_commands, input_pause, match_commands, pause, EnteringConsole, Pause};
-use bevy::{
-    ecs::{archetype::Archetypes, component::Components, entity::Entities},
-    prelude::*,
-    reflect::TypeRegistry,
-    tasks::AsyncComputeTaskPool,
+use crate::app::{
+    add_pause_stages, count_frames, input_pause, run_command_line, run_json_rpc, scale_time,
+    EnteringConsole, FrameStep, Pause, PauseStages, TimeScale,
 };
+use bevy::{prelude::*, tasks::AsyncComputeTaskPool, time::TimeSystem};
 use crossbeam::channel::{bounded, Receiver};
 use std::io::{self, BufRead, Write};
 
-fn parse_input(
-    a: &Archetypes,
-    c: &Components,
-    e: &Entities,
-    reflect: Res<TypeRegistry>,
-    mut pause: ResMut<Pause>,
-    line_channel: Res<Receiver<String>>,
-) {
-    if let Ok(line) = line_channel.try_recv() {
-        let app_name = "";
-        println!("");
-        let split = line.split_whitespace();
-        let mut args = vec![app_name];
-        args.append(&mut split.collect());
-
-        let matches_result = build_commands(app_name).try_get_matches_from(args);
-
-        if let Err(e) = matches_result {
-            println!("{}", e.to_string());
-            print!(">>> ");
-            io::stdout().flush().unwrap();
+fn parse_input(world: &mut World) {
+    let line_result = world.resource::<Receiver<String>>().try_recv();
+    if let Ok(line) = line_result {
+        // json-rpc requests get a single line of json back and no prompt
+        if line.starts_with('{') {
+            println!("{}", run_json_rpc(world, &line));
             return;
         }
 
-        let matches = matches_result.unwrap();
-
-        let output = match_commands(&matches, a, c, e, &mut pause, &*reflect);
+        println!("");
+        let output = run_command_line(world, &line);
 
         println!("{}", output);
         print!(">>> ");
@@ -66,8 +48,16 @@ impl Plugin for ConsoleDebugPlugin {
     fn build(&self, app: &mut App) {
         app.insert_resource(Pause(false))
             .insert_resource(EnteringConsole(false))
+            .init_resource::<FrameStep>()
+            .init_resource::<TimeScale>()
+            .init_resource::<PauseStages>()
+            .add_system_to_stage(CoreStage::Last, count_frames)
+            .add_system_to_stage(CoreStage::First, scale_time.after(TimeSystem))
             .add_startup_system(spawn_io_thread)
-            .add_system(parse_input.with_run_criteria(pause))
+            .add_system_to_stage(CoreStage::PreUpdate, parse_input.exclusive_system())
             .add_system(input_pause);
+
+        let stages = app.world.resource::<PauseStages>().0.clone();
+        add_pause_stages(app, &stages);
     }
 }
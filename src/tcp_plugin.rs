@@ -0,0 +1,200 @@
+use crate::app::{
+    add_pause_stages, count_frames, input_pause, run_command_line, run_json_rpc, scale_time,
+    EnteringConsole, FrameStep, Pause, PauseStages, TimeScale,
+};
+use bevy::{prelude::*, time::TimeSystem};
+use crossbeam::channel::{unbounded, Receiver, Sender};
+use std::{
+    collections::HashMap,
+    io::{BufRead, BufReader, Write},
+    net::{SocketAddr, TcpListener},
+    sync::{Arc, Mutex},
+    thread,
+};
+
+struct TcpConsoleAddress(SocketAddr);
+
+struct TcpConsole {
+    lines: Receiver<(usize, String)>,
+    clients: Arc<Mutex<HashMap<usize, Sender<String>>>>,
+}
+
+fn spawn_listener_thread(mut commands: Commands, address: Res<TcpConsoleAddress>) {
+    let listener = match TcpListener::bind(address.0) {
+        Ok(listener) => listener,
+        Err(err) => {
+            error!("could not start the console on {}: {}", address.0, err);
+            return;
+        }
+    };
+    // with port 0 the os picks the port, so keep the address actually used
+    let address = listener.local_addr().unwrap_or(address.0);
+    info!("Bevy Console Debugger listening on {}", address);
+    commands.insert_resource(TcpConsoleAddress(address));
+
+    let (tx, rx) = unbounded();
+    let clients = Arc::new(Mutex::new(HashMap::new()));
+    let listener_clients = clients.clone();
+    // blocking socket io gets its own threads so it can't starve the task pools
+    thread::spawn(move || {
+        for (id, stream) in listener.incoming().enumerate() {
+            let stream = match stream {
+                Ok(stream) => stream,
+                Err(_) => continue,
+            };
+            let mut writer = match stream.try_clone() {
+                Ok(writer) => writer,
+                Err(_) => continue,
+            };
+            // writes go through the client's own thread, so a client that stops
+            // reading can't block the game
+            let (output_tx, output_rx) = unbounded::<String>();
+            thread::spawn(move || {
+                for output in output_rx {
+                    if write!(writer, "{}", output).is_err() {
+                        break;
+                    }
+                }
+            });
+            let _ = output_tx.send(String::from(
+                "Bevy Console Debugger.  Type 'help' for list of commands.\n>>> ",
+            ));
+            listener_clients.lock().unwrap().insert(id, output_tx);
+
+            let tx = tx.clone();
+            let clients = listener_clients.clone();
+            thread::spawn(move || {
+                for line in BufReader::new(stream).lines() {
+                    match line {
+                        Ok(line) => {
+                            if tx.send((id, line)).is_err() {
+                                break;
+                            }
+                        }
+                        Err(_) => break,
+                    }
+                }
+                clients.lock().unwrap().remove(&id);
+            });
+        }
+    });
+
+    commands.insert_resource(TcpConsole { lines: rx, clients });
+}
+
+fn parse_input(world: &mut World) {
+    let lines: Vec<(usize, String)> = match world.get_resource::<TcpConsole>() {
+        Some(console) => console.lines.try_iter().collect(),
+        None => return,
+    };
+
+    for (client, line) in lines {
+        // json-rpc requests get a single line of json back and no prompt
+        let output = if line.starts_with('{') {
+            format!("{}\n", run_json_rpc(world, &line))
+        } else {
+            format!("{}\n>>> ", run_command_line(world, &line))
+        };
+
+        // only the client that sent the command gets the output
+        let console = world.resource::<TcpConsole>();
+        let clients = console.clients.lock().unwrap();
+        if let Some(sender) = clients.get(&client) {
+            let _ = sender.send(output);
+        }
+    }
+}
+
+/// Serves the console over tcp instead of stdin/stdout. Connect with any line
+/// based client, i.e. `nc 127.0.0.1 7777`.
+pub struct TcpConsolePlugin {
+    pub address: SocketAddr,
+}
+
+impl Default for TcpConsolePlugin {
+    fn default() -> Self {
+        TcpConsolePlugin {
+            address: SocketAddr::from(([127, 0, 0, 1], 7777)),
+        }
+    }
+}
+
+impl Plugin for TcpConsolePlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(Pause(false))
+            .insert_resource(EnteringConsole(false))
+            .init_resource::<FrameStep>()
+            .init_resource::<TimeScale>()
+            .init_resource::<PauseStages>()
+            .add_system_to_stage(CoreStage::Last, count_frames)
+            .add_system_to_stage(CoreStage::First, scale_time.after(TimeSystem))
+            .insert_resource(TcpConsoleAddress(self.address))
+            .add_startup_system(spawn_listener_thread)
+            .add_system_to_stage(CoreStage::PreUpdate, parse_input.exclusive_system())
+            .add_system(input_pause);
+
+        let stages = app.world.resource::<PauseStages>().0.clone();
+        add_pause_stages(app, &stages);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy::{core::CorePlugin, input::InputPlugin, time::TimePlugin};
+    use std::{io::ErrorKind, net::TcpStream, time::Duration};
+
+    fn connect(address: SocketAddr) -> BufReader<TcpStream> {
+        let stream = TcpStream::connect(address).unwrap();
+        stream
+            .set_read_timeout(Some(Duration::from_millis(10)))
+            .unwrap();
+        let mut reader = BufReader::new(stream);
+        // the greeting is sent once the client is known to the console
+        let mut greeting = String::new();
+        while greeting.is_empty() {
+            match reader.read_line(&mut greeting) {
+                Ok(_) => {}
+                Err(err) if matches!(err.kind(), ErrorKind::WouldBlock | ErrorKind::TimedOut) => {}
+                Err(err) => panic!("{}", err),
+            }
+        }
+
+        reader
+    }
+
+    #[test]
+    fn only_the_sending_client_gets_the_output() {
+        let mut app = App::new();
+        app.add_plugin(CorePlugin)
+            .add_plugin(TimePlugin)
+            .add_plugin(InputPlugin)
+            .add_plugin(TcpConsolePlugin {
+                address: SocketAddr::from(([127, 0, 0, 1], 0)),
+            });
+        app.update();
+        let address = app.world.resource::<TcpConsoleAddress>().0;
+
+        let mut sender = connect(address);
+        let mut other = connect(address);
+        writeln!(
+            sender.get_mut(),
+            r#"{{"jsonrpc": "2.0", "id": 1, "method": "counts"}}"#
+        )
+        .unwrap();
+
+        let mut response = String::new();
+        for _ in 0..500 {
+            app.update();
+            if sender.read_line(&mut response).is_ok() && response.ends_with('\n') {
+                break;
+            }
+        }
+        assert!(response.contains(r#""id":1"#), "{}", response);
+        assert!(response.contains(r#""type":"counts""#), "{}", response);
+
+        let mut other_output = String::new();
+        let _ = other.read_line(&mut other_output);
+        assert!(!other_output.contains("jsonrpc"), "{}", other_output);
+    }
+}